@@ -0,0 +1,355 @@
+use std::{any::type_name, borrow::Cow, iter::Map};
+
+use crate::{
+    big_num::{BigInt, BigUint},
+    codec::{MultiValueEncoded, TopDecodeIter},
+    deploy::{Address, ManagedAddress},
+    json::JsonValue,
+    lazy::LazyValue,
+    nested_tuples::{NestedTuple, NestedTupleSplice},
+    payments::Payment,
+    prototype_v6::{
+        ConsNoRet, ConsRet, ConsRetMulti, ListWrapper, OriginalMarker, RetList, RetListItem,
+        TxKind, UnitReturns,
+    },
+};
+
+/// Describes a type the way the contract ABI does, e.g. `u32`, `List<u16>`, `variadic<bytes>`.
+pub trait TypeAbi {
+    fn type_name() -> String;
+
+    /// Name of `Vec<Self>`. Overridden by `u8`, since byte vectors are `bytes` in the ABI.
+    fn list_type_name() -> String {
+        format!("List<{}>", Self::type_name())
+    }
+}
+
+macro_rules! type_abi_by_name {
+    ($($t:ty => $name:literal),+ $(,)?) => {
+        $(
+            impl TypeAbi for $t {
+                fn type_name() -> String {
+                    $name.to_string()
+                }
+            }
+        )+
+    };
+}
+
+type_abi_by_name! {
+    () => "()",
+    bool => "bool",
+    u16 => "u16",
+    u32 => "u32",
+    u64 => "u64",
+    u128 => "u128",
+    i8 => "i8",
+    i16 => "i16",
+    i32 => "i32",
+    i64 => "i64",
+    i128 => "i128",
+    &str => "utf-8 string",
+    String => "utf-8 string",
+    Cow<'_, str> => "utf-8 string",
+    BigUint => "BigUint",
+    BigInt => "BigInt",
+    Payment => "EsdtTokenPayment",
+    Address => "Address",
+    ManagedAddress => "Address",
+}
+
+impl TypeAbi for u8 {
+    fn type_name() -> String {
+        "u8".to_string()
+    }
+
+    fn list_type_name() -> String {
+        "bytes".to_string()
+    }
+}
+
+impl<T: TypeAbi> TypeAbi for Vec<T> {
+    fn type_name() -> String {
+        T::list_type_name()
+    }
+}
+
+impl<T: TypeAbi> TypeAbi for Option<T> {
+    fn type_name() -> String {
+        format!("Option<{}>", T::type_name())
+    }
+}
+
+impl<T: TypeAbi> TypeAbi for MultiValueEncoded<T> {
+    fn type_name() -> String {
+        format!("variadic<{}>", T::type_name())
+    }
+}
+
+/// What `ReturnsIter` produces.
+impl<T: TypeAbi, F> TypeAbi for Map<TopDecodeIter<T>, F> {
+    fn type_name() -> String {
+        MultiValueEncoded::<T>::type_name()
+    }
+}
+
+impl<Original, H, K> TypeAbi for LazyValue<Original, H, K>
+where
+    H: RetListItem<Original, K>,
+    H::Returns: TypeAbi,
+{
+    fn type_name() -> String {
+        H::Returns::type_name()
+    }
+}
+
+/// Flat tuples are multi-value results in this crate, e.g. the `Original` of `ReturnsMultiValue`.
+macro_rules! type_abi_multi {
+    ($(($($t:ident)+))+) => {
+        $(
+            impl<$($t: TypeAbi),+> TypeAbi for ($($t,)+) {
+                fn type_name() -> String {
+                    let names: Vec<String> = vec![$($t::type_name()),+];
+                    format!("multi<{}>", names.join(","))
+                }
+            }
+        )+
+    };
+}
+
+type_abi_multi! {
+    (T1)
+    (T1 T2)
+    (T1 T2 T3)
+    (T1 T2 T3 T4)
+    (T1 T2 T3 T4 T5)
+    (T1 T2 T3 T4 T5 T6)
+    (T1 T2 T3 T4 T5 T6 T7)
+    (T1 T2 T3 T4 T5 T6 T7 T8)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15)
+    (T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16)
+}
+
+/// Type names of each element of a nested tuple, for handlers used with `returns_multi`.
+pub trait NestedTupleAbi: NestedTuple {
+    fn type_names(names: &mut Vec<String>);
+}
+
+impl NestedTupleAbi for () {
+    fn type_names(_names: &mut Vec<String>) {}
+}
+
+impl<Head: TypeAbi, Tail: NestedTupleAbi> NestedTupleAbi for (Head, Tail) {
+    fn type_names(names: &mut Vec<String>) {
+        names.push(Head::type_name());
+        Tail::type_names(names);
+    }
+}
+
+/// One element of the flattened output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputAbi {
+    /// The handler label, or `output_<index>` if it has none.
+    pub name: String,
+    pub type_name: String,
+    pub handler: &'static str,
+}
+
+impl OutputAbi {
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("name", self.name.as_str().into()),
+            ("type", self.type_name.as_str().into()),
+            ("handler", self.handler.into()),
+        ])
+    }
+}
+
+/// Handler lists whose output elements all implement `TypeAbi`.
+pub trait DescribeList: RetList {
+    /// Pushes one entry per output element, in output order.
+    fn describe(&self, outputs: &mut Vec<OutputAbi>);
+}
+
+impl DescribeList for () {
+    fn describe(&self, _outputs: &mut Vec<OutputAbi>) {}
+}
+
+impl<O, K: TxKind> DescribeList for OriginalMarker<O, K> {
+    fn describe(&self, _outputs: &mut Vec<OutputAbi>) {}
+}
+
+/// Name of the output at `index`: the handler label, with the part number appended
+/// for handlers contributing several parts, or `output_<index>` for unlabelled handlers.
+pub(crate) fn output_name(label: Option<&str>, part: usize, parts: usize, index: usize) -> String {
+    match label {
+        Some(label) if parts != 1 => format!("{label}_{part}"),
+        Some(label) => label.to_string(),
+        None => format!("output_{index}"),
+    }
+}
+
+fn describe_item<H, Original, HK>(
+    handler: &H,
+    type_names: Vec<String>,
+    outputs: &mut Vec<OutputAbi>,
+) where
+    H: RetListItem<Original, HK>,
+{
+    let parts = type_names.len();
+    for (part, element_type) in type_names.into_iter().enumerate() {
+        outputs.push(OutputAbi {
+            name: output_name(handler.label(), part, parts, outputs.len()),
+            type_name: element_type,
+            handler: type_name::<H>(),
+        });
+    }
+}
+
+impl<Head, Tail, HK> DescribeList for ConsRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: TypeAbi,
+    Tail: DescribeList,
+{
+    fn describe(&self, outputs: &mut Vec<OutputAbi>) {
+        describe_item(&self.head, vec![Head::Returns::type_name()], outputs);
+        self.tail.describe(outputs);
+    }
+}
+
+impl<Head, Tail, HK> DescribeList for ConsNoRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
+    Tail: DescribeList,
+{
+    fn describe(&self, outputs: &mut Vec<OutputAbi>) {
+        self.tail.describe(outputs);
+    }
+}
+
+impl<Head, Tail, HK> DescribeList for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn> + NestedTupleAbi,
+    Tail: DescribeList,
+{
+    fn describe(&self, outputs: &mut Vec<OutputAbi>) {
+        let mut type_names = Vec::new();
+        Head::Returns::type_names(&mut type_names);
+        describe_item(&self.head, type_names, outputs);
+        self.tail.describe(outputs);
+    }
+}
+
+impl<L: RetList> ListWrapper<L> {
+    pub fn output_abi(&self) -> Vec<OutputAbi>
+    where
+        L: DescribeList,
+    {
+        let mut outputs = Vec::new();
+        self.0.describe(&mut outputs);
+        outputs
+    }
+
+    /// JSON description of what `eval` returns, for generating decoders.
+    pub fn describe_output(&self) -> JsonValue
+    where
+        L: DescribeList,
+        L::Original: TypeAbi,
+    {
+        JsonValue::object([
+            ("original", L::Original::type_name().into()),
+            (
+                "outputs",
+                JsonValue::Array(self.output_abi().iter().map(OutputAbi::to_json).collect()),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{deploy::ReturnsNewAddress, lazy::Lazy, prototype_v6::*};
+
+    #[test]
+    fn test_describe_output() {
+        let list = new_list()
+            .original_marker::<(u32, String)>()
+            .returns(ReturnsDefault)
+            .handle_result(PrintMessage::new("..."))
+            .returns_multi(Labelled::new("pair", ReturnsMultiValue::default()))
+            .returns(Labelled::new("copy", Lazy::new(ReturnsDefault)));
+
+        assert_eq!(
+            list.output_abi()[1],
+            OutputAbi {
+                name: "pair_0".to_string(),
+                type_name: "u32".to_string(),
+                handler: type_name::<Labelled<ReturnsMultiValue<(u32, String)>>>(),
+            }
+        );
+        assert_eq!(
+            list.describe_output().to_string(),
+            concat!(
+                r#"{"original":"multi<u32,utf-8 string>","outputs":["#,
+                r#"{"name":"output_0","type":"multi<u32,utf-8 string>","handler":"rh_prototype::prototype_v6::ReturnsDefault"},"#,
+                r#"{"name":"pair_0","type":"u32","handler":"rh_prototype::prototype_v6::Labelled<rh_prototype::prototype_v6::ReturnsMultiValue<(u32, alloc::string::String)>>"},"#,
+                r#"{"name":"pair_1","type":"utf-8 string","handler":"rh_prototype::prototype_v6::Labelled<rh_prototype::prototype_v6::ReturnsMultiValue<(u32, alloc::string::String)>>"},"#,
+                r#"{"name":"copy","type":"multi<u32,utf-8 string>","handler":"rh_prototype::prototype_v6::Labelled<rh_prototype::lazy::Lazy<rh_prototype::prototype_v6::ReturnsDefault>>"}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_describe_variadic() {
+        let list = new_list()
+            .original_marker::<MultiValueEncoded<Vec<u8>>>()
+            .returns(ReturnsIter::default())
+            .returns(Labelled::new("items", ReturnsVec::default()));
+        let outputs = list.output_abi();
+        assert_eq!(outputs[0].type_name, "variadic<bytes>");
+        assert_eq!(outputs[0].name, "output_0");
+        assert_eq!(outputs[1].type_name, "List<bytes>");
+        assert_eq!(outputs[1].name, "items");
+        assert_eq!(Vec::<u32>::type_name(), "List<u32>");
+        assert_eq!(Vec::<Vec<u8>>::type_name(), "List<bytes>");
+    }
+
+    #[test]
+    fn test_describe_str() {
+        let list = new_list()
+            .original_marker::<i32>()
+            .returns(ReturnsStr::new("x"))
+            .returns(Labelled::new("n", ReturnsInt(1)));
+        let outputs = list.output_abi();
+        assert_eq!(outputs[0].type_name, "utf-8 string");
+        assert_eq!(outputs[1].type_name, "i32");
+        assert_eq!(outputs[1].name, "n");
+    }
+
+    #[test]
+    fn test_describe_deploy() {
+        let list = new_list()
+            .deploy_marker::<u32>()
+            .returns(Labelled::new("result", ReturnsDefault))
+            .returns(Labelled::new("address", ReturnsNewAddress))
+            .returns(Lazy::new(ReturnsNewAddress));
+        assert_eq!(
+            list.describe_output().to_string(),
+            concat!(
+                r#"{"original":"u32","outputs":["#,
+                r#"{"name":"result","type":"u32","handler":"rh_prototype::prototype_v6::Labelled<rh_prototype::prototype_v6::ReturnsDefault>"},"#,
+                r#"{"name":"address","type":"Address","handler":"rh_prototype::prototype_v6::Labelled<rh_prototype::deploy::ReturnsNewAddress>"},"#,
+                r#"{"name":"output_2","type":"Address","handler":"rh_prototype::lazy::Lazy<rh_prototype::deploy::ReturnsNewAddress>"}]}"#,
+            )
+        );
+    }
+}
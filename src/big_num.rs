@@ -0,0 +1,756 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    str::FromStr,
+};
+
+use crate::{
+    codec::{DecodeError, TopDecode, TopEncode},
+    prototype_v6::RetListItem,
+    raw_result::RawResult,
+};
+
+/// Arbitrary-precision unsigned integer, for token amounts and balances.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Little-endian base 2^32 digits, without trailing zeroes, so zero is empty.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint { limbs }
+    }
+
+    /// Leading zeroes are allowed.
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(4)
+            .map(|chunk| chunk.iter().fold(0u32, |limb, b| limb << 8 | *b as u32))
+            .collect();
+        Self::from_limbs(limbs)
+    }
+
+    /// Minimal big-endian bytes, empty for zero.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect();
+        let leading_zeroes = bytes.iter().take_while(|b| **b == 0).count();
+        bytes[leading_zeroes..].to_vec()
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |n, limb| n << 32 | *limb as u128),
+        )
+    }
+
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.limbs
+            .get(index / 32)
+            .is_some_and(|limb| limb >> (index % 32) & 1 == 1)
+    }
+
+    fn shl1_add(&mut self, bit: bool) {
+        let mut carry = bit as u32;
+        for limb in &mut self.limbs {
+            let next = *limb >> 31;
+            *limb = *limb << 1 | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            self.limbs.push(carry);
+        }
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let mut diff = *limb as i64 - other.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = (diff < 0) as i64;
+            if diff < 0 {
+                diff += 1 << 32;
+            }
+            limbs.push(diff as u32);
+        }
+        Some(Self::from_limbs(limbs))
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut rem = 0u64;
+        for (i, limb) in self.limbs.iter().enumerate().rev() {
+            let acc = rem << 32 | *limb as u64;
+            limbs[i] = (acc / divisor as u64) as u32;
+            rem = acc % divisor as u64;
+        }
+        (Self::from_limbs(limbs), rem as u32)
+    }
+
+    fn mul_small_add(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in &mut self.limbs {
+            let acc = *limb as u64 * factor as u64 + carry;
+            *limb = acc as u32;
+            carry = acc >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+        *self = Self::from_limbs(std::mem::take(&mut self.limbs));
+    }
+
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "BigUint: division by zero");
+        if let [small] = divisor.limbs[..] {
+            let (quotient, rem) = self.div_rem_small(small);
+            return (quotient, BigUint::from(rem));
+        }
+        let mut quotient = vec![0u32; self.limbs.len()];
+        let mut rem = BigUint::zero();
+        for index in (0..self.bits()).rev() {
+            rem.shl1_add(self.bit(index));
+            if rem >= *divisor {
+                rem = rem.checked_sub(divisor).unwrap();
+                quotient[index / 32] |= 1 << (index % 32);
+            }
+        }
+        (Self::from_limbs(quotient), rem)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! big_uint_from_unsigned {
+    ($($t:ty)+) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(n: $t) -> Self {
+                    BigUint::from_bytes_be(&n.to_be_bytes())
+                }
+            }
+        )+
+    };
+}
+
+big_uint_from_unsigned! {u8 u16 u32 u64 u128 usize}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let acc = self.limbs.get(i).copied().unwrap_or(0) as u64
+                + other.limbs.get(i).copied().unwrap_or(0) as u64
+                + carry;
+            limbs.push(acc as u32);
+            carry = acc >> 32;
+        }
+        limbs.push(carry as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+/// Panics if the result would be negative, like the primitive unsigned types in debug builds.
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("BigUint: subtraction overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let acc = limbs[i + j] as u64 + *a as u64 * *b as u64 + carry;
+                limbs[i + j] = acc as u32;
+                carry = acc >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+/// The owned forms of the operators, in terms of the borrowed ones.
+macro_rules! forward_owned_ops {
+    ($t:ty; $($op:ident $method:ident)+) => {
+        $(
+            impl $op<$t> for $t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    (&self).$method(&other)
+                }
+            }
+        )+
+    };
+}
+
+forward_owned_ops!(BigUint; Add add Sub sub Mul mul Div div Rem rem);
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+        let mut chunks = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (quotient, rem) = n.div_rem_small(CHUNK);
+            chunks.push(rem);
+            n = quotient;
+        }
+        let mut digits = match chunks.pop() {
+            Some(top) => top.to_string(),
+            None => "0".to_string(),
+        };
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{chunk:09}"));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid decimal integer")
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    /// Decimal digits only.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigIntError);
+        }
+        let mut n = BigUint::zero();
+        for c in s.chars() {
+            let digit = c.to_digit(10).ok_or(ParseBigIntError)?;
+            n.mul_small_add(10, digit);
+        }
+        Ok(n)
+    }
+}
+
+impl TopEncode for BigUint {
+    fn top_encode(&self) -> Vec<u8> {
+        self.to_bytes_be()
+    }
+}
+
+impl TopDecode for BigUint {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(BigUint::from_bytes_be(bytes))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Arbitrary-precision signed integer, as sign and magnitude.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// Never set for zero.
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn from_sign_magnitude(negative: bool, magnitude: BigUint) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.magnitude
+    }
+
+    /// Two's complement, big-endian. Empty is zero.
+    pub fn from_signed_bytes_be(bytes: &[u8]) -> Self {
+        match bytes.first() {
+            Some(first) if first & 0x80 != 0 => {
+                let inverted: Vec<u8> = bytes.iter().map(|b| !b).collect();
+                let magnitude = &BigUint::from_bytes_be(&inverted) + &BigUint::from(1u8);
+                BigInt::from_sign_magnitude(true, magnitude)
+            }
+            _ => BigInt::from(BigUint::from_bytes_be(bytes)),
+        }
+    }
+
+    /// Minimal two's complement, big-endian, empty for zero.
+    pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.magnitude.to_bytes_be();
+        if !self.negative {
+            if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+                bytes.insert(0, 0);
+            }
+            return bytes;
+        }
+
+        // -m is the complement of m - 1, over the same number of bytes.
+        let len = bytes.len();
+        let below = (&self.magnitude - &BigUint::from(1u8)).to_bytes_be();
+        bytes = vec![0xff; len - below.len()];
+        bytes.extend(below.iter().map(|b| !b));
+        if bytes[0] & 0x80 == 0 {
+            bytes.insert(0, 0xff);
+        }
+        bytes
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::from_sign_magnitude(false, magnitude)
+    }
+}
+
+macro_rules! big_int_from_signed {
+    ($($t:ty)+) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(n: $t) -> Self {
+                    BigInt::from_sign_magnitude(n < 0, BigUint::from(n.unsigned_abs()))
+                }
+            }
+        )+
+    };
+}
+
+big_int_from_signed! {i8 i16 i32 i64 i128 isize}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_sign_magnitude(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_sign_magnitude(!self.negative, self.magnitude)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_sign_magnitude(self.negative, &self.magnitude + &other.magnitude);
+        }
+        match self.magnitude.cmp(&other.magnitude) {
+            Ordering::Less => {
+                BigInt::from_sign_magnitude(other.negative, &other.magnitude - &self.magnitude)
+            }
+            _ => BigInt::from_sign_magnitude(self.negative, &self.magnitude - &other.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_sign_magnitude(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+}
+
+/// Rounds towards zero, like the primitive types.
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        BigInt::from_sign_magnitude(
+            self.negative != other.negative,
+            &self.magnitude / &other.magnitude,
+        )
+    }
+}
+
+/// Has the sign of the dividend, like the primitive types.
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        BigInt::from_sign_magnitude(self.negative, &self.magnitude % &other.magnitude)
+    }
+}
+
+forward_owned_ops!(BigInt; Add add Sub sub Mul mul Div div Rem rem);
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Decimal digits, with an optional sign.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(digits) => Ok(BigInt::from_sign_magnitude(true, digits.parse()?)),
+            None => Ok(BigInt::from(
+                s.strip_prefix('+').unwrap_or(s).parse::<BigUint>()?,
+            )),
+        }
+    }
+}
+
+impl TopEncode for BigInt {
+    fn top_encode(&self) -> Vec<u8> {
+        self.to_signed_bytes_be()
+    }
+}
+
+impl TopDecode for BigInt {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(BigInt::from_signed_bytes_be(bytes))
+    }
+}
+
+/// Decimal strings, since most JSON readers cannot hold arbitrary-precision numbers.
+#[cfg(feature = "serde")]
+macro_rules! serde_via_decimal_string {
+    ($($t:ty)+) => {
+        $(
+            impl serde::Serialize for $t {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $t {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let s = String::deserialize(deserializer)?;
+                    s.parse().map_err(serde::de::Error::custom)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "serde")]
+serde_via_decimal_string!(BigUint BigInt);
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Result types encoded as big-endian unsigned numbers.
+pub trait BigUintCompatible {}
+
+/// Result types encoded as big-endian two's complement numbers.
+pub trait BigIntCompatible {}
+
+macro_rules! big_num_compatible {
+    ($tr:ident: $($t:ty)+) => {
+        $(
+            impl $tr for $t {}
+        )+
+    };
+}
+
+big_num_compatible!(BigUintCompatible: u8 u16 u32 u64 u128 usize BigUint);
+big_num_compatible!(BigIntCompatible: i8 i16 i32 i64 i128 isize BigInt);
+
+fn decode_single<T: TopDecode>(raw: &RawResult, handler: &str) -> T {
+    raw.single_out()
+        .and_then(T::top_decode)
+        .unwrap_or_else(|err| panic!("{handler}: {err}"))
+}
+
+/// Decodes a numeric result of any unsigned type as a `BigUint`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsBigUint;
+
+impl<Original: BigUintCompatible> RetListItem<Original> for ReturnsBigUint {
+    type Returns = BigUint;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        decode_single(raw, "ReturnsBigUint")
+    }
+}
+
+/// Decodes a numeric result of any signed type as a `BigInt`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsBigInt;
+
+impl<Original: BigIntCompatible> RetListItem<Original> for ReturnsBigInt {
+    type Returns = BigInt;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        decode_single(raw, "ReturnsBigInt")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prototype_v6::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    fn signed(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_big_uint() {
+        let two_200 = big("1606938044258990275541962092341162602522202993782792835301376");
+        assert_eq!(two_200.bits(), 201);
+        assert_eq!(two_200.to_bytes_be()[0], 1);
+        assert_eq!(two_200.to_bytes_be().len(), 26);
+
+        let divisor = &big("1000000000000000000000000000000") + &BigUint::from(7u8);
+        let (quotient, rem) = two_200.div_rem(&divisor);
+        assert_eq!(quotient.to_string(), "1606938044258990275541962092329");
+        assert_eq!(rem.to_string(), "914036212390061853999100655073");
+        assert_eq!(&(&quotient * &divisor) + &rem, two_200);
+
+        let n = u128::MAX / 3;
+        assert_eq!(BigUint::from(n).to_string(), n.to_string());
+        assert_eq!((BigUint::from(n) + BigUint::from(n)).to_u128(), Some(n * 2));
+        assert_eq!(
+            (BigUint::from(n) - BigUint::from(5u8)).to_u128(),
+            Some(n - 5)
+        );
+        assert_eq!(
+            (BigUint::from(n) * BigUint::from(3u8)).to_u128(),
+            Some(u128::MAX)
+        );
+        assert_eq!((BigUint::from(n) * BigUint::from(4u8)).to_u128(), None);
+        assert_eq!(BigUint::from(7u8).checked_sub(&BigUint::from(8u8)), None);
+        assert!(BigUint::from(u64::MAX) < big("18446744073709551616"));
+
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(format!("{:>5}", BigUint::from(42u8)), "   42");
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigIntError));
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigIntError));
+    }
+
+    #[test]
+    fn test_big_int() {
+        let a = signed("-1361129467683753853853498429727072845819");
+        assert_eq!(
+            a,
+            -(BigInt::from(2) * BigInt::from(BigUint::from(u128::MAX / 2 + 1)) * BigInt::from(4))
+                + BigInt::from(5)
+        );
+        let d = BigInt::from(3i64.pow(20));
+        assert_eq!((&a / &d).to_string(), "-390368118915923145387932584629");
+        assert_eq!((&a % &d).to_string(), "-3063273590");
+        assert_eq!(&(&(&a / &d) * &d) + &(&a % &d), a);
+
+        for (x, y) in [
+            (5i128, -7i128),
+            (-5, 7),
+            (-5, -7),
+            (7, -7),
+            (0, -3),
+            (i64::MIN as i128, 1),
+        ] {
+            assert_eq!(BigInt::from(x) + BigInt::from(y), BigInt::from(x + y));
+            assert_eq!(BigInt::from(x) - BigInt::from(y), BigInt::from(x - y));
+            assert_eq!(BigInt::from(x) * BigInt::from(y), BigInt::from(x * y));
+            assert_eq!(BigInt::from(x) / BigInt::from(y), BigInt::from(x / y));
+            assert_eq!(BigInt::from(x) % BigInt::from(y), BigInt::from(x % y));
+            assert_eq!(BigInt::from(x).cmp(&BigInt::from(y)), x.cmp(&y));
+            assert_eq!(BigInt::from(x).to_string(), x.to_string());
+        }
+        assert!(!(-BigInt::zero()).is_negative());
+        assert_eq!(signed("+12"), BigInt::from(12));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError));
+    }
+
+    #[test]
+    fn test_big_num_codec() {
+        for n in [
+            0i64,
+            1,
+            -1,
+            127,
+            128,
+            -128,
+            -129,
+            255,
+            256,
+            -256,
+            -257,
+            i64::MAX,
+            i64::MIN,
+        ] {
+            let big = BigInt::from(n);
+            assert_eq!(big.top_encode(), n.top_encode(), "{n}");
+            assert_eq!(BigInt::top_decode(&n.top_encode()), Ok(big));
+        }
+        let a = signed("-1361129467683753853853498429727072845819");
+        assert_eq!(
+            crate::encoding::hex_encode(&a.top_encode()),
+            "fc00000000000000000000000000000005"
+        );
+        assert_eq!(BigInt::top_decode(&a.top_encode()), Ok(a));
+
+        let u = big("1361129467683753853853498429727072845819");
+        assert_eq!(
+            crate::encoding::hex_encode(&u.top_encode()),
+            "03fffffffffffffffffffffffffffffffb"
+        );
+        assert_eq!(
+            BigUint::top_decode(&[0, 0, 1, 0]),
+            Ok(BigUint::from(256u16))
+        );
+        assert_eq!(BigUint::zero().top_encode(), Vec::<u8>::new());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_big_num_serde() {
+        let n = signed("-1361129467683753853853498429727072845819");
+        let json = serde_json::to_string(&n).unwrap();
+        assert_eq!(json, r#""-1361129467683753853853498429727072845819""#);
+        assert_eq!(serde_json::from_str::<BigInt>(&json).unwrap(), n);
+        assert!(serde_json::from_str::<BigUint>(r#""-1""#).is_err());
+    }
+
+    #[test]
+    fn test_returns_big_num() {
+        let list = new_list()
+            .original_marker::<u64>()
+            .returns(ReturnsBigUint)
+            .returns(ReturnsDefault);
+        let raw = RawResult::default().with_out(u64::MAX);
+        assert_eq!(list.eval(&raw), (BigUint::from(u64::MAX), 0));
+
+        let list = new_list()
+            .original_marker::<BigInt>()
+            .returns(ReturnsBigInt);
+        let raw = RawResult::default().with_out(BigInt::from(-1000i32));
+        assert_eq!(list.eval(&raw).to_string(), "-1000");
+    }
+}
@@ -0,0 +1,44 @@
+//! Re-checks recorded scenarios: `replay <scenario.json>...`
+//!
+//! Exits with status 1 if any expectation fails, 2 if a scenario cannot be read.
+
+use std::process::ExitCode;
+
+use rh_prototype::scenario::Scenario;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: replay <scenario.json>...");
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        let scenario = match Scenario::read_from_file(path) {
+            Ok(scenario) => scenario,
+            Err(err) => {
+                eprintln!("{path}: {err}");
+                return ExitCode::from(2);
+            }
+        };
+        for (index, step) in scenario.steps.iter().enumerate() {
+            let errors: Vec<String> = step.replay().into_iter().filter_map(Result::err).collect();
+            if errors.is_empty() {
+                println!("{path} step {index}: pass");
+            } else {
+                failed = true;
+                println!("{path} step {index}: FAIL");
+                for error in errors {
+                    println!("    {error}");
+                }
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,315 @@
+use std::{fmt, marker::PhantomData, rc::Rc};
+
+use crate::nested_tuples::tuple_list_type;
+
+/// Minimal version of the top-level encoding used by the MultiversX codec:
+/// numbers are big-endian with leading zeroes (or sign bytes) stripped,
+/// `false` and `0` are empty, byte buffers and strings are taken as they are.
+pub trait TopEncode {
+    fn top_encode(&self) -> Vec<u8>;
+}
+
+pub trait TopDecode: Sized {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeError(&'static str);
+
+impl DecodeError {
+    pub const INPUT_TOO_LONG: DecodeError = DecodeError("input too long");
+    pub const INVALID_VALUE: DecodeError = DecodeError("invalid value");
+    pub const UTF8_DECODE_ERROR: DecodeError = DecodeError("utf-8 decode error");
+    pub const MULTI_TOO_FEW_ARGS: DecodeError = DecodeError("too few results");
+    pub const MULTI_TOO_MANY_ARGS: DecodeError = DecodeError("too many results");
+
+    pub fn message(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl TopEncode for () {
+    fn top_encode(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+impl TopDecode for () {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::INPUT_TOO_LONG)
+        }
+    }
+}
+
+impl TopEncode for bool {
+    fn top_encode(&self) -> Vec<u8> {
+        if *self {
+            vec![1]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl TopDecode for bool {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        match bytes {
+            [] => Ok(false),
+            [1] => Ok(true),
+            [_] => Err(DecodeError::INVALID_VALUE),
+            _ => Err(DecodeError::INPUT_TOO_LONG),
+        }
+    }
+}
+
+macro_rules! top_codec_unsigned {
+    ($($t:ty)+) => {
+        $(
+            impl TopEncode for $t {
+                fn top_encode(&self) -> Vec<u8> {
+                    let bytes = self.to_be_bytes();
+                    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+                    bytes[first..].to_vec()
+                }
+            }
+
+            impl TopDecode for $t {
+                fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+                    const SIZE: usize = std::mem::size_of::<$t>();
+                    if bytes.len() > SIZE {
+                        return Err(DecodeError::INPUT_TOO_LONG);
+                    }
+                    let mut buffer = [0u8; SIZE];
+                    buffer[SIZE - bytes.len()..].copy_from_slice(bytes);
+                    Ok(<$t>::from_be_bytes(buffer))
+                }
+            }
+        )+
+    }
+}
+
+top_codec_unsigned! { u8 u16 u32 u64 u128 }
+
+macro_rules! top_codec_signed {
+    ($($t:ty)+) => {
+        $(
+            impl TopEncode for $t {
+                fn top_encode(&self) -> Vec<u8> {
+                    if *self == 0 {
+                        return Vec::new();
+                    }
+                    let bytes = self.to_be_bytes();
+                    let sign_byte = if *self < 0 { 0xff } else { 0x00 };
+                    // strip redundant sign bytes, keeping the sign bit of the first remaining byte intact
+                    let mut first = 0;
+                    while first + 1 < bytes.len()
+                        && bytes[first] == sign_byte
+                        && (bytes[first + 1] ^ sign_byte) & 0x80 == 0
+                    {
+                        first += 1;
+                    }
+                    bytes[first..].to_vec()
+                }
+            }
+
+            impl TopDecode for $t {
+                fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+                    const SIZE: usize = std::mem::size_of::<$t>();
+                    if bytes.len() > SIZE {
+                        return Err(DecodeError::INPUT_TOO_LONG);
+                    }
+                    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+                    let mut buffer = if negative { [0xffu8; SIZE] } else { [0u8; SIZE] };
+                    buffer[SIZE - bytes.len()..].copy_from_slice(bytes);
+                    Ok(<$t>::from_be_bytes(buffer))
+                }
+            }
+        )+
+    }
+}
+
+top_codec_signed! { i8 i16 i32 i64 i128 }
+
+impl TopEncode for Vec<u8> {
+    fn top_encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+impl TopDecode for Vec<u8> {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl TopEncode for &str {
+    fn top_encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl TopEncode for String {
+    fn top_encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl TopDecode for String {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::UTF8_DECODE_ERROR)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Multi-value results, i.e. several top-encoded values, one per result.
+///
+/// Implemented for flat tuples, which decode into the equivalent nested tuple,
+/// so that each part can be spliced into a handler list's output on its own.
+pub trait TopDecodeMulti {
+    type Nested;
+
+    fn multi_decode(out: &[Vec<u8>]) -> Result<Self::Nested, DecodeError>;
+}
+
+impl TopDecodeMulti for () {
+    type Nested = ();
+
+    fn multi_decode(out: &[Vec<u8>]) -> Result<Self::Nested, DecodeError> {
+        if out.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::MULTI_TOO_MANY_ARGS)
+        }
+    }
+}
+
+macro_rules! top_decode_multi_impl {
+    ($(($len:literal $($n:tt $name:ident)+))+) => {
+        $(
+            impl<$($name: TopDecode),+> TopDecodeMulti for ($($name,)+) {
+                type Nested = tuple_list_type!($($name),+);
+
+                fn multi_decode(out: &[Vec<u8>]) -> Result<Self::Nested, DecodeError> {
+                    if out.len() < $len {
+                        return Err(DecodeError::MULTI_TOO_FEW_ARGS);
+                    }
+                    if out.len() > $len {
+                        return Err(DecodeError::MULTI_TOO_MANY_ARGS);
+                    }
+                    let decoded = ($($name::top_decode(&out[$n])?,)+);
+                    Ok(top_decode_multi_impl!(@nest decoded; $($n)+))
+                }
+            }
+        )+
+    };
+    (@nest $decoded:ident; $n:tt $($ns:tt)*) => {
+        ($decoded.$n, top_decode_multi_impl!(@nest $decoded; $($ns)*))
+    };
+    (@nest $decoded:ident;) => { () };
+}
+
+top_decode_multi_impl! {
+    (1 0 T0)
+    (2 0 T0 1 T1)
+    (3 0 T0 1 T1 2 T2)
+    (4 0 T0 1 T1 2 T2 3 T3)
+    (5 0 T0 1 T1 2 T2 3 T3 4 T4)
+    (6 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    (7 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    (8 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    (9 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    (10 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    (11 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10)
+    (12 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11)
+    (13 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12)
+    (14 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13)
+    (15 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14)
+    (16 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Variable number of results, each holding one top-encoded `T`.
+///
+/// Only describes the result type, it is never instantiated.
+pub struct MultiValueEncoded<T>(PhantomData<T>);
+
+/// Decodes the items of a `MultiValueEncoded` result one at a time, straight from the shared buffers.
+pub struct TopDecodeIter<T> {
+    out: Rc<Vec<Vec<u8>>>,
+    index: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> TopDecodeIter<T> {
+    pub fn new(out: Rc<Vec<Vec<u8>>>) -> Self {
+        TopDecodeIter {
+            out,
+            index: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: TopDecode> Iterator for TopDecodeIter<T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.out.get(self.index)?;
+        self.index += 1;
+        Some(T::top_decode(bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.out.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: TopDecode> ExactSizeIterator for TopDecodeIter<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_top_codec() {
+        assert_eq!(0u32.top_encode(), Vec::<u8>::new());
+        assert_eq!(0x0102u64.top_encode(), vec![1, 2]);
+        assert_eq!(u64::top_decode(&[1, 2]), Ok(0x0102));
+        assert_eq!(u8::top_decode(&[1, 2]), Err(DecodeError::INPUT_TOO_LONG));
+
+        for i in [0i64, 1, -1, 127, 128, -128, -129, 255, -256, i64::MAX, i64::MIN] {
+            assert_eq!(i64::top_decode(&i.top_encode()), Ok(i));
+        }
+        assert_eq!((-1i32).top_encode(), vec![0xff]);
+        assert_eq!(128i32.top_encode(), vec![0x00, 0x80]);
+
+        assert_eq!(bool::top_decode(&[2]), Err(DecodeError::INVALID_VALUE));
+        assert_eq!(String::top_decode(&[0xff]), Err(DecodeError::UTF8_DECODE_ERROR));
+    }
+
+    #[test]
+    fn test_multi_decode() {
+        let out = vec![5u8.top_encode(), true.top_encode(), "abc".top_encode()];
+        let decoded = <(u8, bool, String)>::multi_decode(&out);
+        assert_eq!(decoded, Ok((5, (true, ("abc".to_string(), ())))));
+
+        assert_eq!(
+            <(u8, bool)>::multi_decode(&out),
+            Err(DecodeError::MULTI_TOO_MANY_ARGS)
+        );
+    }
+}
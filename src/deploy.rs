@@ -0,0 +1,164 @@
+use std::rc::Rc;
+
+use crate::{
+    codec::{DecodeError, TopDecode, TopEncode},
+    encoding::{bech32_address, EncodingError, ADDRESS_LEN},
+    prototype_v6::{Deploy, RetListItem},
+    raw_result::RawResult,
+};
+
+/// Account or contract address.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Address(pub [u8; ADDRESS_LEN]);
+
+impl Address {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn to_bech32(&self, hrp: &str) -> Result<String, EncodingError> {
+        bech32_address(hrp, &self.0)
+    }
+}
+
+impl TopEncode for Address {
+    fn top_encode(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl TopDecode for Address {
+    fn top_decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        bytes
+            .try_into()
+            .map(Address)
+            .map_err(|_| DecodeError::INVALID_VALUE)
+    }
+}
+
+/// Shared handle to an address, cheap to clone, as handed out by managed APIs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ManagedAddress(Rc<Address>);
+
+impl ManagedAddress {
+    pub fn as_address(&self) -> &Address {
+        &self.0
+    }
+}
+
+impl From<Address> for ManagedAddress {
+    fn from(address: Address) -> Self {
+        ManagedAddress(Rc::new(address))
+    }
+}
+
+fn new_address_or_panic<'a>(raw: &'a RawResult, handler: &str) -> &'a Address {
+    raw.new_address
+        .as_ref()
+        .unwrap_or_else(|| panic!("{handler}: no new address in the result"))
+}
+
+/// The address of the deployed contract. Only for deploy lists.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsNewAddress;
+
+impl<O> RetListItem<O, Deploy> for ReturnsNewAddress {
+    type Returns = Address;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        *new_address_or_panic(raw, "ReturnsNewAddress")
+    }
+}
+
+/// Like `ReturnsNewAddress`, as a `ManagedAddress`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsNewManagedAddress;
+
+impl<O> RetListItem<O, Deploy> for ReturnsNewManagedAddress {
+    type Returns = ManagedAddress;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        (*new_address_or_panic(raw, "ReturnsNewManagedAddress")).into()
+    }
+}
+
+/// Passes the address of the deployed contract to a closure. Only for deploy lists.
+pub struct WithNewAddress<F: Fn(&Address)>(pub F);
+
+impl<O, F: Fn(&Address)> RetListItem<O, Deploy> for WithNewAddress<F> {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        (self.0)(new_address_or_panic(raw, "WithNewAddress"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{
+        big_num::ReturnsBigUint,
+        expectations::ExpectValue,
+        payments::{Payment, ReturnsEgldBack},
+        prototype_v6::*,
+    };
+
+    #[test]
+    fn test_deploy_handlers() {
+        let address = Address([7; ADDRESS_LEN]);
+        let seen = Cell::new(None);
+        let list = new_list()
+            .deploy_marker::<u32>()
+            .returns(ReturnsNewAddress)
+            .handle_result(WithNewAddress(|addr| seen.set(Some(*addr))))
+            .returns(ReturnsNewManagedAddress)
+            .returns(ReturnsEgldBack)
+            .returns(ReturnsDefault)
+            .returns(ReturnsBigUint)
+            .handle_result(ExpectValue(5u32));
+        let raw = RawResult::default()
+            .with_new_address(address)
+            .with_transfer(Payment::egld(3u8))
+            .with_out(5u32);
+
+        let (new_address, managed, egld, default, result) = list.eval(&raw);
+        assert_eq!(new_address, address);
+        assert_eq!(managed.as_address(), &address);
+        assert_eq!(seen.get(), Some(address));
+        assert_eq!(egld, 3u8.into());
+        assert_eq!(default, 0);
+        assert_eq!(result, 5u8.into());
+    }
+
+    #[test]
+    fn test_upgrade_handlers() {
+        let list = new_list()
+            .upgrade_marker::<u32>()
+            .returns(ReturnsDefault)
+            .handle_result(ExpectValue(5u32));
+        assert_eq!(list.eval(&RawResult::default().with_out(5u32)), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ReturnsNewAddress: no new address in the result")]
+    fn test_missing_new_address() {
+        let list = new_list().deploy_marker::<()>().returns(ReturnsNewAddress);
+        list.eval(&RawResult::default());
+    }
+
+    #[test]
+    fn test_address_codec() {
+        let address = Address::default();
+        assert_eq!(Address::top_decode(&address.top_encode()), Ok(address));
+        assert_eq!(
+            Address::top_decode(&[0; 31]),
+            Err(DecodeError::INVALID_VALUE)
+        );
+        assert_eq!(
+            address.to_bech32("erd").unwrap(),
+            "erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu"
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use std::{any::Any, borrow::Cow, fmt, marker::PhantomData};
+
+use crate::{
+    nested_tuples::NestedTupleSplice,
+    prototype_v6::{
+        ConsNoRet, ConsRet, ConsRetMulti, ListWrapper, OriginalMarker, RetList, RetListItem,
+        TxKind, UnitReturns,
+    },
+    raw_result::RawResult,
+};
+
+/// Object-safe counterpart of `RetListItem`, for lists built at runtime.
+pub trait DynRetListItem<Original> {
+    /// Pushes the outputs of the handler, if any.
+    fn dyn_return(&self, raw: &RawResult, output: &mut Vec<Box<dyn Any>>);
+}
+
+/// Erases a returning handler.
+pub struct DynRet<H>(pub H);
+
+impl<Original, H> DynRetListItem<Original> for DynRet<H>
+where
+    H: RetListItem<Original>,
+    H::Returns: 'static,
+{
+    fn dyn_return(&self, raw: &RawResult, output: &mut Vec<Box<dyn Any>>) {
+        output.push(Box::new(self.0.single_return(raw)));
+    }
+}
+
+/// Erases a handler that returns nothing, the dynamic equivalent of `ConsNoRet`.
+pub struct DynNoRet<H>(pub H);
+
+impl<Original, H> DynRetListItem<Original> for DynNoRet<H>
+where
+    H: RetListItem<Original, Returns: UnitReturns>,
+{
+    fn dyn_return(&self, raw: &RawResult, _output: &mut Vec<Box<dyn Any>>) {
+        self.0.single_return(raw);
+    }
+}
+
+/// Erases a handler that returns several values, the dynamic equivalent of `ConsRetMulti`.
+pub struct DynRetMulti<H>(pub H);
+
+impl<Original, H> DynRetListItem<Original> for DynRetMulti<H>
+where
+    H: RetListItem<Original>,
+    H::Returns: PushAny,
+{
+    fn dyn_return(&self, raw: &RawResult, output: &mut Vec<Box<dyn Any>>) {
+        self.0.single_return(raw).push_any(output);
+    }
+}
+
+/// Boxes each element of a nested tuple separately.
+pub trait PushAny {
+    fn push_any(self, output: &mut Vec<Box<dyn Any>>);
+}
+
+impl PushAny for () {
+    fn push_any(self, _output: &mut Vec<Box<dyn Any>>) {}
+}
+
+impl<Head: 'static, Tail: PushAny> PushAny for (Head, Tail) {
+    fn push_any(self, output: &mut Vec<Box<dyn Any>>) {
+        output.push(Box::new(self.0));
+        self.1.push_any(output);
+    }
+}
+
+pub struct DynRetList<Original> {
+    items: Vec<Box<dyn DynRetListItem<Original>>>,
+    _phantom: PhantomData<Original>,
+}
+
+impl<Original> Default for DynRetList<Original> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Original> DynRetList<Original> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, item: Box<dyn DynRetListItem<Original>>) {
+        self.items.push(item);
+    }
+
+    pub fn returns<H>(mut self, h: H) -> Self
+    where
+        H: RetListItem<Original> + 'static,
+        H::Returns: 'static,
+    {
+        self.items.push(Box::new(DynRet(h)));
+        self
+    }
+
+    pub fn returns_multi<H>(mut self, h: H) -> Self
+    where
+        H: RetListItem<Original> + 'static,
+        H::Returns: PushAny,
+    {
+        self.items.push(Box::new(DynRetMulti(h)));
+        self
+    }
+
+    pub fn handle_result<H>(mut self, h: H) -> Self
+    where
+        H: RetListItem<Original, Returns: UnitReturns> + 'static,
+    {
+        self.items.push(Box::new(DynNoRet(h)));
+        self
+    }
+
+    /// Runs all handlers in order, collecting the outputs of the returning ones.
+    pub fn eval(&self, raw: &RawResult) -> Vec<Box<dyn Any>> {
+        let mut output = Vec::new();
+        for item in &self.items {
+            item.dyn_return(raw, &mut output);
+        }
+        output
+    }
+
+    pub fn eval_values(&self, raw: &RawResult) -> Vec<DynValue> {
+        self.eval(raw).into_iter().map(DynValue::from).collect()
+    }
+
+    /// Evaluates and downcasts the outputs back into the flat tuple a static list would produce.
+    pub fn eval_typed<T: FromDynOutput>(&self, raw: &RawResult) -> Result<T, DynOutputError> {
+        T::from_dyn_output(self.eval(raw))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Moves the handlers of a static list into a `DynRetList`, preserving their order.
+///
+/// Handlers limited to one kind of transaction, like `ReturnsNewAddress`, are not supported.
+pub trait IntoDynItems: RetList {
+    fn into_dyn_items(self, list: &mut DynRetList<Self::Original>);
+}
+
+impl IntoDynItems for () {
+    fn into_dyn_items(self, _list: &mut DynRetList<()>) {}
+}
+
+impl<O, K: TxKind> IntoDynItems for OriginalMarker<O, K> {
+    fn into_dyn_items(self, _list: &mut DynRetList<Self::Original>) {}
+}
+
+impl<Head, Tail> IntoDynItems for ConsRet<Head, Tail>
+where
+    Head: RetListItem<Tail::Original> + 'static,
+    Head::Returns: 'static,
+    Tail: IntoDynItems,
+{
+    fn into_dyn_items(self, list: &mut DynRetList<Self::Original>) {
+        list.push(Box::new(DynRet(self.head)));
+        self.tail.into_dyn_items(list);
+    }
+}
+
+impl<Head, Tail> IntoDynItems for ConsNoRet<Head, Tail>
+where
+    Head: RetListItem<Tail::Original, Returns: UnitReturns> + 'static,
+    Tail: IntoDynItems,
+{
+    fn into_dyn_items(self, list: &mut DynRetList<Self::Original>) {
+        list.push(Box::new(DynNoRet(self.head)));
+        self.tail.into_dyn_items(list);
+    }
+}
+
+impl<Head, Tail> IntoDynItems for ConsRetMulti<Head, Tail>
+where
+    Head: RetListItem<Tail::Original> + 'static,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn> + PushAny,
+    Tail: IntoDynItems,
+{
+    fn into_dyn_items(self, list: &mut DynRetList<Self::Original>) {
+        list.push(Box::new(DynRetMulti(self.head)));
+        self.tail.into_dyn_items(list);
+    }
+}
+
+impl<L: IntoDynItems> ListWrapper<L> {
+    pub fn into_dyn(self) -> DynRetList<L::Original> {
+        let mut list = DynRetList::new();
+        self.0.into_dyn_items(&mut list);
+        list
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Closed set of common output types, for callers that would rather `match` than downcast.
+#[derive(Debug)]
+pub enum DynValue {
+    Unit,
+    Bool(bool),
+    I32(i32),
+    I64(i64),
+    U64(u64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Other(Box<dyn Any>),
+}
+
+impl From<Box<dyn Any>> for DynValue {
+    fn from(value: Box<dyn Any>) -> Self {
+        let value = match value.downcast::<()>() {
+            Ok(_) => return DynValue::Unit,
+            Err(value) => value,
+        };
+        let value = match value.downcast::<bool>() {
+            Ok(b) => return DynValue::Bool(*b),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<i32>() {
+            Ok(i) => return DynValue::I32(*i),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<i64>() {
+            Ok(i) => return DynValue::I64(*i),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<u64>() {
+            Ok(u) => return DynValue::U64(*u),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<&'static str>() {
+            Ok(s) => return DynValue::Str(s.to_string()),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<String>() {
+            Ok(s) => return DynValue::Str(*s),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<Cow<'static, str>>() {
+            Ok(s) => return DynValue::Str(s.into_owned()),
+            Err(value) => value,
+        };
+        match value.downcast::<Vec<u8>>() {
+            Ok(bytes) => DynValue::Bytes(*bytes),
+            Err(value) => DynValue::Other(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynOutputError {
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    WrongType {
+        index: usize,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for DynOutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynOutputError::WrongLength { expected, found } => {
+                write!(f, "expected {expected} outputs, found {found}")
+            }
+            DynOutputError::WrongType { index, expected } => {
+                write!(f, "output {index} is not of type `{expected}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DynOutputError {}
+
+pub trait FromDynOutput: Sized {
+    fn from_dyn_output(output: Vec<Box<dyn Any>>) -> Result<Self, DynOutputError>;
+}
+
+fn downcast_at<T: 'static>(index: usize, value: Box<dyn Any>) -> Result<T, DynOutputError> {
+    value
+        .downcast::<T>()
+        .map(|b| *b)
+        .map_err(|_| DynOutputError::WrongType {
+            index,
+            expected: std::any::type_name::<T>(),
+        })
+}
+
+macro_rules! from_dyn_output_impl {
+    ($(($len:literal $($n:tt $name:ident)*))+) => {
+        $(
+            impl<$($name: 'static),*> FromDynOutput for ($($name,)*) {
+                fn from_dyn_output(output: Vec<Box<dyn Any>>) -> Result<Self, DynOutputError> {
+                    if output.len() != $len {
+                        return Err(DynOutputError::WrongLength {
+                            expected: $len,
+                            found: output.len(),
+                        });
+                    }
+                    let mut it = output.into_iter();
+                    Ok(($(downcast_at::<$name>($n, it.next().unwrap())?,)*))
+                }
+            }
+        )+
+    }
+}
+
+from_dyn_output_impl! {
+    (0)
+    (1 0 T0)
+    (2 0 T0 1 T1)
+    (3 0 T0 1 T1 2 T2)
+    (4 0 T0 1 T1 2 T2 3 T3)
+    (5 0 T0 1 T1 2 T2 3 T3 4 T4)
+    (6 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    (7 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    (8 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    (9 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    (10 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    (11 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10)
+    (12 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11)
+    (13 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12)
+    (14 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13)
+    (15 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14)
+    (16 0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prototype_v6::*;
+
+    #[test]
+    fn test_into_dyn() {
+        let list = new_list()
+            .original_marker::<i32>()
+            .returns(ReturnsInt(100))
+            .returns(ReturnsStr::new("hello!"))
+            .handle_result(PrintMessage::new("handling results ..."))
+            .returns(ReturnsDefault);
+
+        let raw = RawResult::default();
+        let dyn_list = list.into_dyn();
+        assert_eq!(dyn_list.len(), 4);
+
+        let typed = dyn_list.eval_typed::<(i32, Cow<'static, str>, i32)>(&raw);
+        assert_eq!(typed, Ok((100, "hello!".into(), 0)));
+
+        assert_eq!(
+            dyn_list.eval_typed::<(i32, i32, i32)>(&raw),
+            Err(DynOutputError::WrongType {
+                index: 1,
+                expected: "i32"
+            })
+        );
+        assert_eq!(
+            dyn_list.eval_typed::<(i32,)>(&raw),
+            Err(DynOutputError::WrongLength {
+                expected: 1,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_into_dyn_multi() {
+        let raw = RawResult::default().with_out(5u32).with_out("abc");
+        let list = new_list()
+            .original_marker::<(u32, String)>()
+            .returns(ReturnsDefault)
+            .returns_multi(ReturnsMultiValue::default());
+
+        let dyn_list = list.into_dyn();
+        assert_eq!(dyn_list.len(), 2);
+        assert_eq!(
+            dyn_list.eval_typed::<((u32, String), u32, String)>(&raw),
+            Ok(((0, String::new()), 5, "abc".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_runtime_built() {
+        let config = ["int:7", "default", "print:handling results ...", "str"];
+        let mut list = DynRetList::<i32>::new();
+        for entry in config {
+            match entry.split_once(':') {
+                Some(("int", n)) => list.push(Box::new(DynRet(ReturnsInt(n.parse().unwrap())))),
+                Some(("print", message)) => {
+                    list.push(Box::new(DynNoRet(PrintMessage::new(message.to_string()))))
+                }
+                None if entry == "default" => list.push(Box::new(DynRet(ReturnsDefault))),
+                _ => list.push(Box::new(DynRet(ReturnsStr::new(entry)))),
+            }
+        }
+
+        let values = list.eval_values(&RawResult::default());
+        assert!(matches!(
+            values.as_slice(),
+            [DynValue::I32(7), DynValue::I32(0), DynValue::Str(s)] if s == "str"
+        ));
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt;
+
+/// Lowercase hex, two digits per byte, no prefix.
+pub fn hex_encode(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push(DIGITS[(b >> 4) as usize] as char);
+        s.push(DIGITS[(b & 0x0f) as usize] as char);
+    }
+    s
+}
+
+/// Accepts both cases. No prefix.
+pub fn hex_decode(s: &str) -> Result<Vec<u8>, EncodingError> {
+    let digits = s
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(EncodingError::InvalidHex)?;
+    if !digits.len().is_multiple_of(2) {
+        return Err(EncodingError::InvalidHex);
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodingError {
+    /// Odd number of digits, or characters outside `0-9a-fA-F`.
+    InvalidHex,
+    InvalidUtf8 {
+        /// Length of the longest valid prefix.
+        valid_up_to: usize,
+    },
+    InvalidBase64,
+    /// Human-readable part empty, too long, or with characters outside `!`..=`~`.
+    InvalidHrp(String),
+    Bech32TooLong(usize),
+    Bech32MixedCase,
+    Bech32MissingSeparator,
+    Bech32InvalidChar(char),
+    Bech32InvalidChecksum,
+    /// Leftover bits when converting back to bytes.
+    Bech32InvalidPadding,
+    InvalidAddressLength(usize),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::InvalidHex => f.write_str("invalid hex"),
+            EncodingError::InvalidUtf8 { valid_up_to } => {
+                write!(f, "invalid utf-8 after {valid_up_to} bytes")
+            }
+            EncodingError::InvalidBase64 => f.write_str("invalid base64"),
+            EncodingError::InvalidHrp(hrp) => write!(f, "invalid bech32 prefix `{hrp}`"),
+            EncodingError::Bech32TooLong(len) => {
+                write!(f, "bech32 string of {len} characters exceeds 90")
+            }
+            EncodingError::Bech32MixedCase => {
+                f.write_str("bech32 string mixes upper and lower case")
+            }
+            EncodingError::Bech32MissingSeparator => f.write_str("bech32 separator `1` missing"),
+            EncodingError::Bech32InvalidChar(c) => write!(f, "invalid bech32 character `{c}`"),
+            EncodingError::Bech32InvalidChecksum => f.write_str("invalid bech32 checksum"),
+            EncodingError::Bech32InvalidPadding => f.write_str("invalid bech32 padding"),
+            EncodingError::InvalidAddressLength(len) => {
+                write!(f, "addresses are {ADDRESS_LEN} bytes, got {len}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+pub fn utf8_decode(bytes: &[u8]) -> Result<String, EncodingError> {
+    std::str::from_utf8(bytes)
+        .map(str::to_string)
+        .map_err(|err| EncodingError::InvalidUtf8 {
+            valid_up_to: err.valid_up_to(),
+        })
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard alphabet, with padding.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+/// Standard alphabet. Padding is required.
+pub fn base64_decode(s: &str) -> Result<Vec<u8>, EncodingError> {
+    if !s.len().is_multiple_of(4) {
+        return Err(EncodingError::InvalidBase64);
+    }
+    let mut bytes = Vec::with_capacity(s.len() / 4 * 3);
+    let quads = s.as_bytes().chunks(4);
+    let last = quads.len().saturating_sub(1);
+    for (index, quad) in quads.enumerate() {
+        let padding = quad.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || (padding > 0 && index != last) {
+            return Err(EncodingError::InvalidBase64);
+        }
+        let mut n = 0u32;
+        for (i, c) in quad[..4 - padding].iter().enumerate() {
+            let digit = BASE64_ALPHABET
+                .iter()
+                .position(|a| a == c)
+                .ok_or(EncodingError::InvalidBase64)?;
+            n |= (digit as u32) << (18 - 6 * i);
+        }
+        let decoded = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        // The bits dropped by the padding must be zero, or the encoding is not canonical.
+        if decoded[3 - padding..].iter().any(|b| *b != 0) {
+            return Err(EncodingError::InvalidBase64);
+        }
+        bytes.extend_from_slice(&decoded[..3 - padding]);
+    }
+    Ok(bytes)
+}
+
+/// Length of an account address, in bytes.
+pub const ADDRESS_LEN: usize = 32;
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_MAX_LEN: usize = 90;
+
+fn bech32_polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    values.into_iter().fold(1, |chk, v| {
+        let top = chk >> 25;
+        let chk = (chk & 0x1ffffff) << 5 ^ v as u32;
+        (0..5)
+            .filter(|i| (top >> i) & 1 == 1)
+            .fold(chk, |chk, i| chk ^ GENERATOR[i])
+    })
+}
+
+fn bech32_hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|b| b & 0x1f))
+}
+
+fn check_hrp(hrp: &str) -> Result<(), EncodingError> {
+    if hrp.is_empty() || hrp.len() > 83 || hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err(EncodingError::InvalidHrp(hrp.to_string()));
+    }
+    Ok(())
+}
+
+/// Regroups bits, e.g. 8-bit bytes into 5-bit bech32 digits.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, EncodingError> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    let max = (1 << to) - 1;
+    for value in data {
+        acc = acc << from | *value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push((acc >> bits & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push((acc << (to - bits) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits) & max) != 0 {
+        return Err(EncodingError::Bech32InvalidPadding);
+    }
+    Ok(out)
+}
+
+/// Original bech32 (BIP-173), as used for account addresses. The prefix is lowercased.
+pub fn bech32_encode(hrp: &str, data: &[u8]) -> Result<String, EncodingError> {
+    check_hrp(hrp)?;
+    let hrp = hrp.to_ascii_lowercase();
+    let digits = convert_bits(data, 8, 5, true)?;
+    let len = hrp.len() + 1 + digits.len() + 6;
+    if len > BECH32_MAX_LEN {
+        return Err(EncodingError::Bech32TooLong(len));
+    }
+
+    let polymod = bech32_polymod(
+        bech32_hrp_expand(&hrp)
+            .chain(digits.iter().copied())
+            .chain([0; 6]),
+    ) ^ 1;
+    let checksum = (0..6).map(|i| (polymod >> (5 * (5 - i)) & 0x1f) as u8);
+
+    let mut s = String::with_capacity(len);
+    s.push_str(&hrp);
+    s.push('1');
+    s.extend(
+        digits
+            .iter()
+            .copied()
+            .chain(checksum)
+            .map(|d| BECH32_CHARSET[d as usize] as char),
+    );
+    Ok(s)
+}
+
+/// Returns the prefix, lowercased, and the data bytes.
+pub fn bech32_decode(s: &str) -> Result<(String, Vec<u8>), EncodingError> {
+    if s.len() > BECH32_MAX_LEN {
+        return Err(EncodingError::Bech32TooLong(s.len()));
+    }
+    if s.bytes().any(|b| b.is_ascii_lowercase()) && s.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(EncodingError::Bech32MixedCase);
+    }
+    let s = s.to_ascii_lowercase();
+    let (hrp, data) = s
+        .rsplit_once('1')
+        .ok_or(EncodingError::Bech32MissingSeparator)?;
+    check_hrp(hrp)?;
+    if data.len() < 6 {
+        return Err(EncodingError::Bech32InvalidChecksum);
+    }
+    let digits = data
+        .chars()
+        .map(|c| {
+            BECH32_CHARSET
+                .iter()
+                .position(|d| *d as char == c)
+                .map(|d| d as u8)
+                .ok_or(EncodingError::Bech32InvalidChar(c))
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    if bech32_polymod(bech32_hrp_expand(hrp).chain(digits.iter().copied())) != 1 {
+        return Err(EncodingError::Bech32InvalidChecksum);
+    }
+    let bytes = convert_bits(&digits[..digits.len() - 6], 5, 8, false)?;
+    Ok((hrp.to_string(), bytes))
+}
+
+/// Bech32 form of an account address, e.g. `erd1...`.
+pub fn bech32_address(hrp: &str, address: &[u8]) -> Result<String, EncodingError> {
+    if address.len() != ADDRESS_LEN {
+        return Err(EncodingError::InvalidAddressLength(address.len()));
+    }
+    bech32_encode(hrp, address)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex_encode(&[]), "");
+        assert_eq!(hex_encode(&[0x00, 0x0f, 0xa5, 0xff]), "000fa5ff");
+        assert_eq!(hex_decode("000fA5ff"), Ok(vec![0x00, 0x0f, 0xa5, 0xff]));
+        assert_eq!(hex_decode(""), Ok(vec![]));
+        assert_eq!(hex_decode("abc"), Err(EncodingError::InvalidHex));
+        assert_eq!(hex_decode("+1"), Err(EncodingError::InvalidHex));
+    }
+
+    #[test]
+    fn test_utf8() {
+        assert_eq!(utf8_decode("héllo".as_bytes()), Ok("héllo".to_string()));
+        assert_eq!(
+            utf8_decode(&[b'a', 0xff, b'b']),
+            Err(EncodingError::InvalidUtf8 { valid_up_to: 1 })
+        );
+    }
+
+    #[test]
+    fn test_base64() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(base64_encode(plain.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded), Ok(plain.as_bytes().to_vec()));
+        }
+        assert_eq!(base64_encode(&[0xfb, 0xff]), "+/8=");
+        for invalid in ["Zg=", "Zg=a", "Z===", "Zh==", "Zg==Zg==", "Z!=="] {
+            assert_eq!(
+                base64_decode(invalid),
+                Err(EncodingError::InvalidBase64),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bech32() {
+        assert_eq!(bech32_encode("a", &[]), Ok("a12uel5l".to_string()));
+        assert_eq!(
+            bech32_address("erd", &[0; 32]),
+            Ok("erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu".to_string())
+        );
+
+        let address: Vec<u8> = (0..32).collect();
+        let encoded = bech32_address("erd", &address).unwrap();
+        assert_eq!(
+            bech32_decode(&encoded),
+            Ok(("erd".to_string(), address.clone()))
+        );
+        assert_eq!(
+            bech32_decode(&encoded.to_uppercase()),
+            Ok(("erd".to_string(), address))
+        );
+
+        assert_eq!(bech32_decode("A12UEL5L"), Ok(("a".to_string(), Vec::new())));
+        assert_eq!(
+            bech32_decode("A12uEL5L"),
+            Err(EncodingError::Bech32MixedCase)
+        );
+        assert_eq!(
+            bech32_decode("a12uel5m"),
+            Err(EncodingError::Bech32InvalidChecksum)
+        );
+        assert_eq!(
+            bech32_decode("a12ueb5l"),
+            Err(EncodingError::Bech32InvalidChar('b'))
+        );
+        assert_eq!(
+            bech32_decode("qqqqqqqq"),
+            Err(EncodingError::Bech32MissingSeparator)
+        );
+        assert_eq!(
+            bech32_encode("", &[1]),
+            Err(EncodingError::InvalidHrp(String::new()))
+        );
+        assert_eq!(
+            bech32_encode("erd", &[0; 60]),
+            Err(EncodingError::Bech32TooLong(106))
+        );
+        assert_eq!(
+            bech32_address("erd", &[0; 20]),
+            Err(EncodingError::InvalidAddressLength(20))
+        );
+    }
+}
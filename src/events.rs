@@ -0,0 +1,234 @@
+use std::marker::PhantomData;
+
+use crate::{
+    codec::{DecodeError, TopDecodeMulti, TopEncode},
+    expectations::{out_from_json, out_to_json, Expectation},
+    json::JsonValue,
+    nested_tuples::Flatten,
+    prototype_v6::RetListItem,
+    raw_result::RawResult,
+};
+
+/// An event emitted during the call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Log {
+    pub identifier: String,
+    /// Indexed fields, one top-encoded buffer each.
+    pub topics: Vec<Vec<u8>>,
+    /// Other fields, one top-encoded buffer each.
+    pub data: Vec<Vec<u8>>,
+}
+
+impl Log {
+    pub fn new(identifier: &str) -> Self {
+        Log {
+            identifier: identifier.to_string(),
+            ..Log::default()
+        }
+    }
+
+    pub fn with_topic<T: TopEncode>(mut self, topic: T) -> Self {
+        self.topics.push(topic.top_encode());
+        self
+    }
+
+    pub fn with_data<T: TopEncode>(mut self, data: T) -> Self {
+        self.data.push(data.top_encode());
+        self
+    }
+
+    /// Topics and data as `"0x..."` strings.
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("identifier", self.identifier.as_str().into()),
+            ("topics", out_to_json(&self.topics)),
+            ("data", out_to_json(&self.data)),
+        ])
+    }
+
+    /// Inverse of `to_json`.
+    pub fn from_json(json: &JsonValue) -> Option<Log> {
+        Some(Log {
+            identifier: json.get("identifier")?.as_str()?.to_string(),
+            topics: out_from_json(json.get("topics")?)?,
+            data: out_from_json(json.get("data")?)?,
+        })
+    }
+}
+
+/// The fields of a multi-value layout, as a flat tuple, or as the value itself if there is one.
+pub type Fields<T> = <<T as TopDecodeMulti>::Nested as Flatten>::Unpacked;
+
+/// Schema of a typed event.
+///
+/// `Topics` and `Data` are flat tuples of the field types, in emission order. `()` if empty.
+pub trait Event: Sized {
+    const IDENTIFIER: &'static str;
+
+    type Topics: TopDecodeMulti<Nested: Flatten>;
+    type Data: TopDecodeMulti<Nested: Flatten>;
+
+    fn from_fields(topics: Fields<Self::Topics>, data: Fields<Self::Data>) -> Self;
+
+    /// Does not check the identifier.
+    fn decode_log(log: &Log) -> Result<Self, DecodeError> {
+        let topics = Self::Topics::multi_decode(&log.topics)?;
+        let data = Self::Data::multi_decode(&log.data)?;
+        Ok(Self::from_fields(
+            topics.flatten_unpack(),
+            data.flatten_unpack(),
+        ))
+    }
+}
+
+fn decode_events<E: Event>(raw: &RawResult, handler: &str) -> Vec<E> {
+    raw.logs
+        .iter()
+        .filter(|log| log.identifier == E::IDENTIFIER)
+        .map(|log| E::decode_log(log).unwrap_or_else(|err| panic!("{handler}: {err}")))
+        .collect()
+}
+
+/// Decodes every log with the identifier of `E`, in emission order.
+///
+/// Panics if one of them does not fit the schema.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct ReturnsEvents<E>(PhantomData<E>);
+
+impl<E> Default for ReturnsEvents<E> {
+    fn default() -> Self {
+        ReturnsEvents(PhantomData)
+    }
+}
+
+impl<Original, E: Event> RetListItem<Original> for ReturnsEvents<E> {
+    type Returns = Vec<E>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        decode_events(raw, "ReturnsEvents")
+    }
+}
+
+/// Checks that the call emitted at least one `E` event. Panics otherwise.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct ExpectEvent<E>(PhantomData<E>);
+
+impl<E> Default for ExpectEvent<E> {
+    fn default() -> Self {
+        ExpectEvent(PhantomData)
+    }
+}
+
+impl<Original, E: Event> RetListItem<Original> for ExpectEvent<E> {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        if decode_events::<E>(raw, "ExpectEvent").is_empty() {
+            panic!("ExpectEvent: no `{}` event", E::IDENTIFIER);
+        }
+    }
+
+    /// Replays only check that the event was emitted, since the schema is not recorded.
+    fn expectation(&self) -> Option<Expectation> {
+        Some(Expectation::Event(E::IDENTIFIER.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{big_num::BigUint, prototype_v6::*};
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct SwapEvent {
+        token_in: String,
+        token_out: String,
+        amount_in: BigUint,
+        amount_out: BigUint,
+    }
+
+    impl Event for SwapEvent {
+        const IDENTIFIER: &'static str = "swap";
+
+        type Topics = (String, String);
+        type Data = (BigUint, BigUint);
+
+        fn from_fields(
+            (token_in, token_out): (String, String),
+            (amount_in, amount_out): (BigUint, BigUint),
+        ) -> Self {
+            SwapEvent {
+                token_in,
+                token_out,
+                amount_in,
+                amount_out,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Paused(u64);
+
+    impl Event for Paused {
+        const IDENTIFIER: &'static str = "paused";
+
+        type Topics = (u64,);
+        type Data = ();
+
+        fn from_fields(timestamp: u64, _data: ()) -> Self {
+            Paused(timestamp)
+        }
+    }
+
+    fn swap_log(amount_in: u32, amount_out: u32) -> Log {
+        Log::new("swap")
+            .with_topic("WEGLD")
+            .with_topic("USDC")
+            .with_data(BigUint::from(amount_in))
+            .with_data(BigUint::from(amount_out))
+    }
+
+    #[test]
+    fn test_returns_events() {
+        let raw = RawResult::default()
+            .with_log(swap_log(10, 300))
+            .with_log(Log::new("paused").with_topic(1_700_000_000u64))
+            .with_log(swap_log(1, 29));
+        let list = new_list()
+            .original_marker::<()>()
+            .handle_result(ExpectEvent::<Paused>::default())
+            .returns(ReturnsEvents::<SwapEvent>::default())
+            .returns(ReturnsEvents::<Paused>::default());
+        let (swaps, paused) = list.eval(&raw);
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(swaps[1].token_out, "USDC");
+        assert_eq!(swaps[1].amount_out, BigUint::from(29u8));
+        assert_eq!(paused, vec![Paused(1_700_000_000)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ExpectEvent: no `paused` event")]
+    fn test_expect_event_missing() {
+        let list = new_list()
+            .original_marker::<()>()
+            .handle_result(ExpectEvent::<Paused>::default());
+        list.eval(&RawResult::default().with_log(swap_log(10, 300)));
+    }
+
+    #[test]
+    #[should_panic(expected = "ReturnsEvents: too few results")]
+    fn test_invalid_event() {
+        let list = new_list()
+            .original_marker::<()>()
+            .returns(ReturnsEvents::<SwapEvent>::default());
+        list.eval(&RawResult::default().with_log(Log::new("swap").with_topic("WEGLD")));
+    }
+}
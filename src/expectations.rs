@@ -0,0 +1,173 @@
+use std::fmt::Debug;
+
+use crate::{
+    codec::TopEncode,
+    encoding::{hex_decode, hex_encode},
+    json::JsonValue,
+    payments::Payment,
+    prototype_v6::RetListItem,
+    raw_result::RawResult,
+};
+
+/// A check on the raw result, described as data.
+///
+/// Expectation handlers report one of these, so that the check can be written to a scenario
+/// and run again later, without the handler types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// All the results, top-encoded, in order.
+    Out(Vec<Vec<u8>>),
+    /// A payment among the tokens sent back.
+    Transfer(Payment),
+    /// An event with this identifier among the logs. Its fields are not checked.
+    Event(String),
+}
+
+impl Expectation {
+    pub fn check(&self, raw: &RawResult) -> Result<(), String> {
+        match self {
+            Expectation::Out(expected) => {
+                if raw.out.as_slice() == expected.as_slice() {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected out {}, got {}",
+                        out_to_json(expected),
+                        out_to_json(&raw.out)
+                    ))
+                }
+            }
+            Expectation::Transfer(expected) => {
+                if raw.transfers.contains(expected) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected transfer {expected}, got {}",
+                        transfers_to_json(&raw.transfers)
+                    ))
+                }
+            }
+            Expectation::Event(identifier) => {
+                if raw.logs.iter().any(|log| &log.identifier == identifier) {
+                    Ok(())
+                } else {
+                    Err(format!("no `{identifier}` event"))
+                }
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            Expectation::Out(expected) => JsonValue::object([("out", out_to_json(expected))]),
+            Expectation::Transfer(expected) => {
+                JsonValue::object([("transfer", expected.to_json())])
+            }
+            Expectation::Event(identifier) => {
+                JsonValue::object([("event", identifier.as_str().into())])
+            }
+        }
+    }
+
+    /// Inverse of `to_json`. `None` if the value does not describe an expectation.
+    pub fn from_json(json: &JsonValue) -> Option<Expectation> {
+        if let Some(out) = json.get("out") {
+            out_from_json(out).map(Expectation::Out)
+        } else if let Some(transfer) = json.get("transfer") {
+            Payment::from_json(transfer).map(Expectation::Transfer)
+        } else {
+            let identifier = json.get("event")?.as_str()?;
+            Some(Expectation::Event(identifier.to_string()))
+        }
+    }
+}
+
+/// Result buffers as `"0x..."` strings.
+pub fn out_to_json(out: &[Vec<u8>]) -> JsonValue {
+    JsonValue::Array(
+        out.iter()
+            .map(|bytes| format!("0x{}", hex_encode(bytes)).into())
+            .collect(),
+    )
+}
+
+/// Inverse of `out_to_json`.
+pub fn out_from_json(json: &JsonValue) -> Option<Vec<Vec<u8>>> {
+    json.as_array()?
+        .iter()
+        .map(|item| hex_decode(item.as_str()?.strip_prefix("0x")?).ok())
+        .collect()
+}
+
+pub fn transfers_to_json(transfers: &[Payment]) -> JsonValue {
+    JsonValue::Array(transfers.iter().map(Payment::to_json).collect())
+}
+
+/// Inverse of `transfers_to_json`.
+pub fn transfers_from_json(json: &JsonValue) -> Option<Vec<Payment>> {
+    json.as_array()?.iter().map(Payment::from_json).collect()
+}
+
+/// Checks that the results are exactly the given buffers. Panics otherwise.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpectOut(pub Vec<Vec<u8>>);
+
+impl<Original> RetListItem<Original> for ExpectOut {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        if let Err(err) = Expectation::Out(self.0.clone()).check(raw) {
+            panic!("ExpectOut: {err}");
+        }
+    }
+
+    fn expectation(&self) -> Option<Expectation> {
+        Some(Expectation::Out(self.0.clone()))
+    }
+}
+
+/// Checks that the single result is the given value. Panics otherwise.
+///
+/// Compared in encoded form, so it is recorded the same way as `ExpectOut`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpectValue<T>(pub T);
+
+impl<T: TopEncode> RetListItem<T> for ExpectValue<T> {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        let expected = Expectation::Out(vec![self.0.top_encode()]);
+        if let Err(err) = expected.check(raw) {
+            panic!("ExpectValue: {err}");
+        }
+    }
+
+    fn expectation(&self) -> Option<Expectation> {
+        Some(Expectation::Out(vec![self.0.top_encode()]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prototype_v6::*;
+
+    #[test]
+    fn test_expect_value() {
+        let list = new_list()
+            .original_marker::<u32>()
+            .handle_result(ExpectValue(300u32))
+            .handle_result(ExpectOut(vec![vec![0x01, 0x2c]]))
+            .returns(ReturnsDefault);
+        assert_eq!(list.eval(&RawResult::default().with_out(300u32)), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"ExpectValue: expected out ["0x012c"], got ["0x05"]"#)]
+    fn test_expect_value_mismatch() {
+        let list = new_list()
+            .original_marker::<u32>()
+            .handle_result(ExpectValue(300u32));
+        list.eval(&RawResult::default().with_out(5u32));
+    }
+}
@@ -1,41 +1,201 @@
+use crate::nested_tuples::{tuple_list_type, Flatten, Nest, NestedTuple, NestedTupleSplice};
+
+/// A flat tuple, `(A, B, C)`, and its nested counterpart, `(A, (B, (C, ())))`.
+pub trait FlatTuple: Sized {
+    const LEN: usize;
+
+    type Nested: NestedTuple;
+
+    fn into_nested(self) -> Self::Nested;
+
+    fn from_nested(nested: Self::Nested) -> Self;
+}
+
 pub trait TupleFlatPush<X> {
     type Output;
 
     fn flat_push(self, x: X) -> Self::Output;
 }
 
-impl<T> TupleFlatPush<T> for () {
-    type Output = (T,);
+/// Splits off the last element.
+pub trait TupleFlatPop {
+    type Init;
+    type Last;
 
-    fn flat_push(self, t: T) -> Self::Output {
-        (t,)
-    }
+    fn flat_pop(self) -> (Self::Init, Self::Last);
 }
 
-impl<T, A> TupleFlatPush<T> for (A,) {
-    type Output = (A, T);
+pub trait TupleConcat<Other> {
+    type Output;
 
-    fn flat_push(self, t: T) -> Self::Output {
-        (self.0, t)
-    }
+    fn flat_concat(self, other: Other) -> Self::Output;
 }
 
-impl<T, A, B> TupleFlatPush<T> for (A, B) {
-    type Output = (A, B, T);
+/// Goes through the nested representation, so it covers every pair of tuples whose
+/// concatenation has a `Flatten` impl.
+impl<A, B> TupleConcat<B> for A
+where
+    A: FlatTuple,
+    B: FlatTuple,
+    A::Nested: NestedTupleSplice<B::Nested>,
+    <A::Nested as NestedTupleSplice<B::Nested>>::Output: Flatten,
+{
+    type Output = <<A::Nested as NestedTupleSplice<B::Nested>>::Output as Flatten>::Flattened;
 
-    fn flat_push(self, t: T) -> Self::Output {
-        (self.0, self.1, t)
+    fn flat_concat(self, other: B) -> Self::Output {
+        self.into_nested().splice(other.into_nested()).flatten()
     }
 }
 
-impl<T, A, B, C> TupleFlatPush<T> for (A, B, C) {
-    type Output = (A, B, C, T);
+/// The first `N` elements, and the rest.
+pub trait TupleSplitAt<const N: usize> {
+    type Left;
+    type Right;
 
-    fn flat_push(self, t: T) -> Self::Output {
-        (self.0, self.1, self.2, t)
-    }
+    fn flat_split_at(self) -> (Self::Left, Self::Right);
 }
 
+pub trait TupleReverse {
+    type Reversed;
+
+    fn flat_reverse(self) -> Self::Reversed;
+}
+
+/// Tuples whose elements all have the same type, `T`.
+pub trait TupleMap<T> {
+    type Mapped<U>;
+
+    /// Applies `f` to each element, first to last.
+    fn map_each<U, F: FnMut(T) -> U>(self, f: F) -> Self::Mapped<U>;
+}
+
+macro_rules! replace {
+    ($_t:tt $sub:tt) => {
+        $sub
+    };
+}
+
+macro_rules! flat_tuple_impls {
+    ($(($($i:tt $t:ident)*))+) => {
+        $(
+            flat_tuple_impls!(@one $($i $t)*);
+        )+
+    };
+    (@one $($i:tt $t:ident)*) => {
+        impl<$($t),*> FlatTuple for ($($t,)*) {
+            const LEN: usize = 0 $(+ replace!($t 1))*;
+
+            type Nested = tuple_list_type!($($t),*);
+
+            fn into_nested(self) -> Self::Nested {
+                self.nest()
+            }
+
+            fn from_nested(nested: Self::Nested) -> Self {
+                nested.flatten()
+            }
+        }
+
+        impl<$($t,)* X> TupleFlatPush<X> for ($($t,)*) {
+            type Output = ($($t,)* X,);
+
+            fn flat_push(self, x: X) -> Self::Output {
+                ($(self.$i,)* x,)
+            }
+        }
+
+        flat_tuple_impls!(@map $($i $t)*);
+        flat_tuple_impls!(@reverse [$(($i $t))*] [$(($i $t))*] []);
+        flat_tuple_impls!(@split [$(($i $t))*] [] [$(($i $t))*]);
+    };
+
+    (@map) => {};
+    (@map $($i:tt $t:ident)+) => {
+        impl<T> TupleMap<T> for ($(replace!($t T),)+) {
+            type Mapped<U> = ($(replace!($t U),)+);
+
+            fn map_each<U, F: FnMut(T) -> U>(self, mut f: F) -> Self::Mapped<U> {
+                ($(f(self.$i),)+)
+            }
+        }
+    };
+
+    // [all elements] [not yet reversed] [reversed so far]
+    (@reverse [$(($i:tt $t:ident))*] [] [$(($ri:tt $rt:ident))*]) => {
+        impl<$($t),*> TupleReverse for ($($t,)*) {
+            type Reversed = ($($rt,)*);
+
+            #[allow(clippy::unused_unit)]
+            fn flat_reverse(self) -> Self::Reversed {
+                ($(self.$ri,)*)
+            }
+        }
+    };
+    (@reverse [$($all:tt)*] [$first:tt $($rest:tt)*] [$($acc:tt)*]) => {
+        flat_tuple_impls!(@reverse [$($all)*] [$($rest)*] [$first $($acc)*]);
+    };
+
+    // [all elements] [left] [right]
+    (@split [$(($i:tt $t:ident))*] [$(($li:tt $lt:ident))*] []) => {
+        impl<$($t),*> TupleSplitAt<{ 0 $(+ replace!($lt 1))* }> for ($($t,)*) {
+            type Left = ($($lt,)*);
+            type Right = ();
+
+            fn flat_split_at(self) -> (Self::Left, Self::Right) {
+                (($(self.$li,)*), ())
+            }
+        }
+    };
+    (@split [$(($i:tt $t:ident))*] [$(($li:tt $lt:ident))*] [($fi:tt $ft:ident)]) => {
+        impl<$($t),*> TupleFlatPop for ($($t,)*) {
+            type Init = ($($lt,)*);
+            type Last = $ft;
+
+            fn flat_pop(self) -> (Self::Init, Self::Last) {
+                (($(self.$li,)*), self.$fi)
+            }
+        }
+
+        flat_tuple_impls!(@split_step [$(($i $t))*] [$(($li $lt))*] [($fi $ft)]);
+    };
+    (@split [$($all:tt)*] [$($left:tt)*] [$($right:tt)+]) => {
+        flat_tuple_impls!(@split_step [$($all)*] [$($left)*] [$($right)+]);
+    };
+    (@split_step [$(($i:tt $t:ident))*] [$(($li:tt $lt:ident))*] [($fi:tt $ft:ident) $(($ri:tt $rt:ident))*]) => {
+        impl<$($t),*> TupleSplitAt<{ 0 $(+ replace!($lt 1))* }> for ($($t,)*) {
+            type Left = ($($lt,)*);
+            type Right = ($ft, $($rt,)*);
+
+            fn flat_split_at(self) -> (Self::Left, Self::Right) {
+                (($(self.$li,)*), (self.$fi, $(self.$ri,)*))
+            }
+        }
+
+        flat_tuple_impls!(@split [$(($i $t))*] [$(($li $lt))* ($fi $ft)] [$(($ri $rt))*]);
+    };
+}
+
+// One line per arity. The nested conversions go through `Nest` and `Flatten`,
+// so raising the maximum arity here also requires extending `flatten_impl!`.
+flat_tuple_impls! {
+    ()
+    (0 T0)
+    (0 T0 1 T1)
+    (0 T0 1 T1 2 T2)
+    (0 T0 1 T1 2 T2 3 T3)
+    (0 T0 1 T1 2 T2 3 T3 4 T4)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14)
+    (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
+}
 
 pub trait TupleUnpack {
     type Unpacked;
@@ -90,3 +250,43 @@ tuple_unpack_self! {
     (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
 }
 
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_flat_tuples() {
+        let t = (1u8, "two", 3u16);
+        assert_eq!(<(u8, &str, u16)>::LEN, 3);
+        assert_eq!(t.into_nested(), (1u8, ("two", (3u16, ()))));
+        assert_eq!(<(u8, &str, u16)>::from_nested(t.into_nested()), t);
+
+        assert_eq!(t.flat_push('4'), (1u8, "two", 3u16, '4'));
+        assert_eq!(t.flat_pop(), ((1u8, "two"), 3u16));
+        assert_eq!((1,).flat_pop(), ((), 1));
+
+        assert_eq!(t.flat_concat((4u32, 5u64)), (1u8, "two", 3u16, 4u32, 5u64));
+        assert_eq!(().flat_concat(t), t);
+        assert_eq!(t.flat_concat(()), t);
+
+        assert_eq!(TupleSplitAt::<0>::flat_split_at(t), ((), t));
+        assert_eq!(TupleSplitAt::<1>::flat_split_at(t), ((1u8,), ("two", 3u16)));
+        assert_eq!(TupleSplitAt::<3>::flat_split_at(t), (t, ()));
+
+        assert_eq!(t.flat_reverse(), (3u16, "two", 1u8));
+        assert_eq!(().flat_reverse(), ());
+
+        assert_eq!((1, 2, 3).map_each(|x| x * 10), (10, 20, 30));
+        assert_eq!(("a", "bc").map_each(str::len), (1, 2));
+    }
+
+    #[test]
+    fn test_max_arity() {
+        let t = (0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8, 13u8, 14u8, 15u8);
+        let (left, right) = TupleSplitAt::<8>::flat_split_at(t);
+        assert_eq!(left.flat_concat(right).map_each(u32::from).flat_reverse().0, 15u32);
+        assert_eq!(left, (0, 1, 2, 3, 4, 5, 6, 7));
+        assert_eq!(right.flat_reverse(), (15, 14, 13, 12, 11, 10, 9, 8));
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    cell::OnceCell,
+    fmt::{self, Debug},
+    marker::PhantomData,
+    ops::Deref,
+    rc::Rc,
+};
+
+use crate::{
+    expectations::Expectation,
+    prototype_v6::{AnyKind, RetListItem},
+    raw_result::RawResult,
+};
+
+/// Defers a returning handler until its output is first used.
+///
+/// The list output gets a `LazyValue` in its place, which keeps the raw result around,
+/// runs the handler on first access, and caches what it returned.
+pub struct Lazy<H>(Rc<H>);
+
+impl<H> Lazy<H> {
+    pub fn new(handler: H) -> Self {
+        Lazy(Rc::new(handler))
+    }
+}
+
+impl<Original, K, H> RetListItem<Original, K> for Lazy<H>
+where
+    H: RetListItem<Original, K>,
+{
+    type Returns = LazyValue<Original, H, K>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        LazyValue {
+            handler: self.0.clone(),
+            raw: raw.clone(),
+            value: OnceCell::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn expectation(&self) -> Option<Expectation> {
+        self.0.expectation()
+    }
+
+    fn label(&self) -> Option<&str> {
+        self.0.label()
+    }
+}
+
+pub struct LazyValue<Original, H, K = AnyKind>
+where
+    H: RetListItem<Original, K>,
+{
+    handler: Rc<H>,
+    raw: RawResult,
+    value: OnceCell<H::Returns>,
+    _phantom: PhantomData<fn() -> (Original, K)>,
+}
+
+impl<Original, H, K> LazyValue<Original, H, K>
+where
+    H: RetListItem<Original, K>,
+{
+    /// Runs the handler the first time, then returns the cached value.
+    pub fn get(&self) -> &H::Returns {
+        self.value
+            .get_or_init(|| self.handler.single_return(&self.raw))
+    }
+
+    pub fn into_inner(self) -> H::Returns {
+        self.get();
+        self.value.into_inner().unwrap()
+    }
+
+    pub fn is_evaluated(&self) -> bool {
+        self.value.get().is_some()
+    }
+}
+
+impl<Original, H, K> Deref for LazyValue<Original, H, K>
+where
+    H: RetListItem<Original, K>,
+{
+    type Target = H::Returns;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+/// Does not force the value, so that tracing and logging leave it unevaluated.
+impl<Original, H, K> Debug for LazyValue<Original, H, K>
+where
+    H: RetListItem<Original, K>,
+    H::Returns: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value.get() {
+            Some(value) => f.debug_tuple("LazyValue").field(value).finish(),
+            None => f.write_str("LazyValue(<not evaluated>)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{codec::MultiValueEncoded, expectations::ExpectOut, prototype_v6::*};
+
+    struct CountCalls(Rc<Cell<usize>>);
+
+    impl<Original> RetListItem<Original> for CountCalls {
+        type Returns = usize;
+
+        fn single_return(&self, raw: &RawResult) -> Self::Returns {
+            self.0.set(self.0.get() + 1);
+            raw.out.len()
+        }
+    }
+
+    #[test]
+    fn test_lazy() {
+        let calls = Rc::new(Cell::new(0));
+        let list = new_list()
+            .original_marker::<MultiValueEncoded<u32>>()
+            .returns(Lazy::new(CountCalls(calls.clone())))
+            .returns(CountCalls(calls.clone()))
+            .returns(Lazy::new(ReturnsVec::default()));
+
+        let raw = RawResult::default().with_out(1u32).with_out(2u32);
+        let (count, eager_count, values) = list.eval(&raw);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(eager_count, 2);
+        assert_eq!(format!("{count:?}"), "LazyValue(<not evaluated>)");
+
+        assert_eq!(*count.get(), 2);
+        assert_eq!(*count, 2);
+        assert_eq!(calls.get(), 2);
+        assert_eq!(format!("{count:?}"), "LazyValue(2)");
+
+        assert!(!values.is_evaluated());
+        assert_eq!(values.into_inner(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_lazy_forwards_label_and_expectation() {
+        let labelled = Lazy::new(Labelled::new("count", ReturnsDefault));
+        assert_eq!(RetListItem::<u32>::label(&labelled), Some("count"));
+
+        let expect = Lazy::new(ExpectOut(vec![vec![5]]));
+        assert_eq!(
+            RetListItem::<u32>::expectation(&expect),
+            Some(Expectation::Out(vec![vec![5]]))
+        );
+    }
+}
@@ -0,0 +1,29 @@
+#![allow(unused)]
+
+pub mod abi;
+pub mod big_num;
+pub mod codec;
+pub mod deploy;
+pub mod dyn_ret_list;
+pub mod encoding;
+pub mod events;
+pub mod expectations;
+pub mod flat_tuples;
+pub mod json;
+pub mod lazy;
+pub mod mock_vm;
+pub mod nested_tuples;
+pub mod payments;
+pub mod presentation;
+pub mod prototype_v1;
+pub mod prototype_v2;
+pub mod prototype_v3;
+pub mod prototype_v4;
+pub mod prototype_v5;
+pub mod prototype_v6;
+pub mod raw_result;
+pub mod result_logger;
+pub mod scenario;
+#[cfg(feature = "serde")]
+pub mod serde_output;
+pub mod tx_batch;
@@ -1,14 +1,3 @@
-#![allow(unused)]
-
-mod flat_tuples;
-mod nested_tuples;
-mod prototype_v1;
-mod prototype_v2;
-mod prototype_v3;
-mod prototype_v4;
-mod prototype_v5;
-mod prototype_v6;
-
 fn main() {
-    prototype_v6::example();
+    rh_prototype::prototype_v6::example();
 }
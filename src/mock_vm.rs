@@ -0,0 +1,96 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{codec::TopEncode, raw_result::RawResult};
+
+/// A contract call: endpoint name and top-encoded arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxCall {
+    pub endpoint: String,
+    pub args: Vec<Vec<u8>>,
+}
+
+impl TxCall {
+    pub fn new(endpoint: &str) -> Self {
+        TxCall {
+            endpoint: endpoint.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with_arg<T: TopEncode>(mut self, arg: T) -> Self {
+        self.args.push(arg.top_encode());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxError {
+    /// The endpoint signalled an error.
+    Failed {
+        status: u64,
+        message: String,
+    },
+    UnknownEndpoint(String),
+    /// Not executed, because an earlier transaction in the same batch failed.
+    Skipped,
+    /// Executed, but one of its result handlers panicked, with the panic message.
+    HandlerPanicked(String),
+}
+
+impl TxError {
+    pub fn user_error(message: &str) -> Self {
+        TxError::Failed {
+            status: 4,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::Failed { status, message } => write!(f, "error {status}: {message}"),
+            TxError::UnknownEndpoint(endpoint) => write!(f, "unknown endpoint `{endpoint}`"),
+            TxError::Skipped => f.write_str("skipped after an earlier failure"),
+            TxError::HandlerPanicked(message) => write!(f, "result handler panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+type Endpoint = Box<dyn FnMut(&[Vec<u8>]) -> Result<RawResult, TxError>>;
+
+/// Stand-in for a local VM: endpoints are plain closures, executed synchronously.
+#[derive(Default)]
+pub struct MockVm {
+    endpoints: HashMap<String, Endpoint>,
+    executed: Vec<String>,
+}
+
+impl MockVm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<F>(&mut self, endpoint: &str, f: F)
+    where
+        F: FnMut(&[Vec<u8>]) -> Result<RawResult, TxError> + 'static,
+    {
+        self.endpoints.insert(endpoint.to_string(), Box::new(f));
+    }
+
+    pub fn execute(&mut self, call: &TxCall) -> Result<RawResult, TxError> {
+        let endpoint = self
+            .endpoints
+            .get_mut(&call.endpoint)
+            .ok_or_else(|| TxError::UnknownEndpoint(call.endpoint.clone()))?;
+        self.executed.push(call.endpoint.clone());
+        endpoint(&call.args)
+    }
+
+    /// Endpoints called so far, in call order.
+    pub fn executed(&self) -> &[String] {
+        &self.executed
+    }
+}
@@ -1,8 +1,19 @@
-pub trait NestedTuple {}
+use std::fmt::Debug;
 
-impl NestedTuple for () {}
+pub trait NestedTuple {
+    const LEN: usize;
+}
+
+impl NestedTuple for () {
+    const LEN: usize = 0;
+}
 
-impl<Head, Tail> NestedTuple for (Head, Tail) where Tail: NestedTuple {}
+impl<Head, Tail> NestedTuple for (Head, Tail)
+where
+    Tail: NestedTuple,
+{
+    const LEN: usize = Tail::LEN + 1;
+}
 
 pub trait NestedTupleAppend<T> {
     type Output;
@@ -29,6 +40,258 @@ where
     }
 }
 
+/// Appends all elements of another nested tuple at once,
+/// e.g. `(A, (B, ()))` spliced with `(C, (D, ()))` gives `(A, (B, (C, (D, ()))))`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a nested tuple, so it cannot be spliced with `{Other}`",
+    note = "handlers passed to `returns_multi` must return nested tuples, such as `(A, (B, ()))`"
+)]
+pub trait NestedTupleSplice<Other: NestedTuple> {
+    type Output: NestedTuple;
+
+    fn splice(self, other: Other) -> Self::Output;
+}
+
+impl<Other: NestedTuple> NestedTupleSplice<Other> for () {
+    type Output = Other;
+
+    fn splice(self, other: Other) -> Self::Output {
+        other
+    }
+}
+
+impl<Head, Tail, Other> NestedTupleSplice<Other> for (Head, Tail)
+where
+    Tail: NestedTupleSplice<Other>,
+    Other: NestedTuple,
+{
+    type Output = (Head, Tail::Output);
+
+    fn splice(self, other: Other) -> Self::Output {
+        (self.0, self.1.splice(other))
+    }
+}
+
+/// Stores many nested tuples of the same type column by column,
+/// e.g. values of type `(A, (B, ()))` are pushed into `(Vec<A>, (Vec<B>, ()))`.
+pub trait NestedTupleColumns: NestedTuple {
+    type Columns: NestedTuple + Default;
+
+    fn push_into(self, columns: &mut Self::Columns);
+}
+
+impl NestedTupleColumns for () {
+    type Columns = ();
+
+    fn push_into(self, _columns: &mut Self::Columns) {}
+}
+
+impl<Head, Tail> NestedTupleColumns for (Head, Tail)
+where
+    Tail: NestedTupleColumns,
+{
+    type Columns = (Vec<Head>, Tail::Columns);
+
+    fn push_into(self, columns: &mut Self::Columns) {
+        columns.0.push(self.0);
+        self.1.push_into(&mut columns.1);
+    }
+}
+
+pub trait NestedTuplePrepend<T>: NestedTuple {
+    fn prepend(self, t: T) -> (T, Self)
+    where
+        Self: Sized;
+}
+
+impl<N: NestedTuple, T> NestedTuplePrepend<T> for N {
+    fn prepend(self, t: T) -> (T, Self) {
+        (t, self)
+    }
+}
+
+/// Moves the elements, last first, onto the front of `Acc`.
+pub trait NestedTupleReverseOnto<Acc: NestedTuple> {
+    type Output: NestedTuple;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output;
+}
+
+impl<Acc: NestedTuple> NestedTupleReverseOnto<Acc> for () {
+    type Output = Acc;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output {
+        acc
+    }
+}
+
+impl<Head, Tail, Acc> NestedTupleReverseOnto<Acc> for (Head, Tail)
+where
+    Tail: NestedTupleReverseOnto<(Head, Acc)>,
+    Acc: NestedTuple,
+{
+    type Output = Tail::Output;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output {
+        self.1.reverse_onto((self.0, acc))
+    }
+}
+
+pub trait NestedTupleReverse: NestedTuple {
+    type Reversed: NestedTuple;
+
+    fn reverse(self) -> Self::Reversed;
+}
+
+impl<N> NestedTupleReverse for N
+where
+    N: NestedTuple + NestedTupleReverseOnto<()>,
+{
+    type Reversed = N::Output;
+
+    fn reverse(self) -> Self::Reversed {
+        self.reverse_onto(())
+    }
+}
+
+/// Non-empty nested tuples, split into the last element and everything before it.
+pub trait NestedTupleLast: NestedTuple {
+    type Last;
+
+    fn last(self) -> Self::Last;
+}
+
+impl<Head> NestedTupleLast for (Head, ()) {
+    type Last = Head;
+
+    fn last(self) -> Self::Last {
+        self.0
+    }
+}
+
+impl<Head, Next, Tail> NestedTupleLast for (Head, (Next, Tail))
+where
+    (Next, Tail): NestedTupleLast,
+{
+    type Last = <(Next, Tail) as NestedTupleLast>::Last;
+
+    fn last(self) -> Self::Last {
+        self.1.last()
+    }
+}
+
+pub trait NestedTupleInit: NestedTuple {
+    type Init: NestedTuple;
+
+    fn init(self) -> Self::Init;
+}
+
+impl<Head> NestedTupleInit for (Head, ()) {
+    type Init = ();
+
+    fn init(self) -> Self::Init {}
+}
+
+impl<Head, Next, Tail> NestedTupleInit for (Head, (Next, Tail))
+where
+    (Next, Tail): NestedTupleInit,
+{
+    type Init = (Head, <(Next, Tail) as NestedTupleInit>::Init);
+
+    fn init(self) -> Self::Init {
+        (self.0, self.1.init())
+    }
+}
+
+/// Folds elements of different types into a single accumulator.
+///
+/// Implement `FoldItem<T>` for every element type the folder should accept.
+pub trait Folder {
+    type Acc;
+}
+
+pub trait FoldItem<T>: Folder {
+    fn fold_item(&mut self, acc: Self::Acc, item: T) -> Self::Acc;
+}
+
+pub trait NestedTupleFoldWith<F: Folder>: NestedTuple {
+    /// Folds the elements first to last.
+    fn fold_with(self, init: F::Acc, folder: &mut F) -> F::Acc;
+}
+
+impl<F: Folder> NestedTupleFoldWith<F> for () {
+    fn fold_with(self, init: F::Acc, _folder: &mut F) -> F::Acc {
+        init
+    }
+}
+
+impl<F, Head, Tail> NestedTupleFoldWith<F> for (Head, Tail)
+where
+    F: FoldItem<Head>,
+    Tail: NestedTupleFoldWith<F>,
+{
+    fn fold_with(self, init: F::Acc, folder: &mut F) -> F::Acc {
+        let acc = folder.fold_item(init, self.0);
+        self.1.fold_with(acc, folder)
+    }
+}
+
+pub trait NestedTupleForEachDebug: NestedTuple {
+    fn for_each_debug(&self, f: &mut dyn FnMut(&dyn Debug));
+
+    /// Each element, formatted with `Debug`.
+    fn debug_strings(&self) -> Vec<String> {
+        let mut strings = Vec::with_capacity(Self::LEN);
+        self.for_each_debug(&mut |item| strings.push(format!("{item:?}")));
+        strings
+    }
+}
+
+impl NestedTupleForEachDebug for () {
+    fn for_each_debug(&self, _f: &mut dyn FnMut(&dyn Debug)) {}
+}
+
+impl<Head, Tail> NestedTupleForEachDebug for (Head, Tail)
+where
+    Head: Debug,
+    Tail: NestedTupleForEachDebug,
+{
+    fn for_each_debug(&self, f: &mut dyn FnMut(&dyn Debug)) {
+        f(&self.0);
+        self.1.for_each_debug(f);
+    }
+}
+
+/// Pairs up the elements of two nested tuples of the same length,
+/// e.g. `(A, (B, ()))` zipped with `(X, (Y, ()))` gives `((A, X), ((B, Y), ()))`.
+pub trait NestedTupleZip<Other: NestedTuple>: NestedTuple {
+    type Zipped: NestedTuple;
+
+    fn zip(self, other: Other) -> Self::Zipped;
+}
+
+impl NestedTupleZip<()> for () {
+    type Zipped = ();
+
+    fn zip(self, _other: ()) -> Self::Zipped {}
+}
+
+impl<Head, Tail, OtherHead, OtherTail> NestedTupleZip<(OtherHead, OtherTail)> for (Head, Tail)
+where
+    Tail: NestedTupleZip<OtherTail>,
+    OtherTail: NestedTuple,
+{
+    type Zipped = ((Head, OtherHead), Tail::Zipped);
+
+    fn zip(self, other: (OtherHead, OtherTail)) -> Self::Zipped {
+        ((self.0, other.0), self.1.zip(other.1))
+    }
+}
+
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be flattened",
+    note = "only nested tuples of up to 16 elements can be flattened, i.e. at most 16 returning handlers per list"
+)]
 pub trait Flatten: NestedTuple {
     type Flattened;
     type Unpacked;
@@ -58,12 +321,44 @@ impl<T> Flatten for (T, ()) {
     }
 }
 
+/// Inverse of `Flatten`: lifts `(A, B, C)` into `(A, (B, (C, ())))`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be converted to a nested tuple",
+    note = "only flat tuples of up to 16 elements can be nested"
+)]
+pub trait Nest {
+    type Nested: NestedTuple;
+
+    fn nest(self) -> Self::Nested;
+}
+
+impl Nest for () {
+    type Nested = ();
+
+    fn nest(self) -> Self::Nested {}
+}
+
+impl<T> Nest for (T,) {
+    type Nested = (T, ());
+
+    fn nest(self) -> Self::Nested {
+        (self.0, ())
+    }
+}
+
 macro_rules! tuple_list_type {
     () => ( () );
     ($i:ty)  => ( ($i, ()) );
     ($i:ty, $($e:ty),*)  => ( ($i, tuple_list_type!($($e),*)) );
 }
 
+pub(crate) use tuple_list_type;
+
+macro_rules! tuple_list {
+    () => ( () );
+    ($e:expr $(, $rest:expr)*) => ( ($e, tuple_list!($($rest),*)) );
+}
+
 macro_rules! unnest {
     (($layer:expr); ($($v:expr),*); ($u:ident, $($us:ident,)*)) => {
         unnest!(($layer . 1); ($($v,)* $layer . 0); ($($us,)*))
@@ -86,6 +381,16 @@ macro_rules! flatten_impl {
                     self.flatten()
                 }
             }
+
+            impl<$t,$($ts),+> Nest for ($t,$($ts),+) {
+                type Nested = tuple_list_type!($t,$($ts),+);
+
+                #[allow(non_snake_case)]
+                fn nest(self) -> Self::Nested {
+                    let ($t, $($ts),+) = self;
+                    tuple_list!($t, $($ts),+)
+                }
+            }
         )+
     }
 }
@@ -125,4 +430,80 @@ mod test {
         let flat4 = n4.flatten();
         assert_eq!(flat4, (1u8, 2u16, 3u32, 4u64));
     }
+
+    macro_rules! test_round_trip {
+        ($(($($v:expr),*))+) => {
+            $(
+                let flat = ($($v,)*);
+                let nested = flat.nest();
+                assert_eq!(nested, tuple_list!($($v),*));
+                assert_eq!(nested.flatten().nest(), nested);
+            )+
+        };
+    }
+
+    #[test]
+    fn test_nest_round_trip() {
+        test_round_trip! {
+            ()
+            (1u8)
+            (1u8, "2")
+            (1u8, "2", 3i64)
+            (1u8, "2", 3i64, 4)
+            (1u8, "2", 3i64, 4, 5)
+            (1u8, "2", 3i64, 4, 5, 6)
+            (1u8, "2", 3i64, 4, 5, 6, 7)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10, 11)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10, 11, 12)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+            (1u8, "2", 3i64, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16)
+        }
+    }
+
+    struct TotalSize;
+
+    impl Folder for TotalSize {
+        type Acc = usize;
+    }
+
+    impl<T> FoldItem<T> for TotalSize {
+        fn fold_item(&mut self, acc: usize, _item: T) -> usize {
+            acc + std::mem::size_of::<T>()
+        }
+    }
+
+    #[test]
+    fn test_hlist_ops() {
+        let n = (1u8, ("two", (3u32, ())));
+        assert_eq!(<(u8, (&str, (u32, ())))>::LEN, 3);
+        assert_eq!(<()>::LEN, 0);
+
+        assert_eq!(n.prepend('0'), ('0', n));
+        assert_eq!(n.reverse(), (3u32, ("two", (1u8, ()))));
+        assert_eq!(().reverse(), ());
+        assert_eq!(n.last(), 3u32);
+        assert_eq!(n.init(), (1u8, ("two", ())));
+        assert_eq!((1, ()).init(), ());
+
+        assert_eq!(n.fold_with(0, &mut TotalSize), 1 + 2 * std::mem::size_of::<usize>() + 4);
+        assert_eq!(n.debug_strings(), vec!["1", "\"two\"", "3"]);
+        assert_eq!(
+            n.zip((true, (None::<i8>, ('c', ())))),
+            ((1u8, true), (("two", None), ((3u32, 'c'), ())))
+        );
+    }
+
+    #[test]
+    fn test_splice() {
+        let n2 = (1u8, (2u16, ()));
+        let n4 = n2.splice((3u32, (4u64, ())));
+        assert_eq!(n4, (1u8, (2u16, (3u32, (4u64, ())))));
+        assert_eq!(().splice(n4), n4);
+        assert_eq!(n4.splice(()), n4);
+    }
 }
@@ -0,0 +1,223 @@
+use std::{borrow::Cow, fmt};
+
+use crate::{
+    big_num::BigUint, expectations::Expectation, json::JsonValue, prototype_v6::RetListItem,
+    raw_result::RawResult,
+};
+
+/// Identifier of the native token, in payment lists.
+pub const EGLD_TOKEN_ID: &str = "EGLD";
+
+/// A token transfer: fungible if the nonce is 0, NFT/SFT otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Payment {
+    pub token_identifier: String,
+    pub nonce: u64,
+    pub amount: BigUint,
+}
+
+impl Payment {
+    pub fn egld<A: Into<BigUint>>(amount: A) -> Self {
+        Payment::esdt(EGLD_TOKEN_ID, 0, amount)
+    }
+
+    pub fn esdt<A: Into<BigUint>>(token_identifier: &str, nonce: u64, amount: A) -> Self {
+        Payment {
+            token_identifier: token_identifier.to_string(),
+            nonce,
+            amount: amount.into(),
+        }
+    }
+
+    pub fn is_egld(&self) -> bool {
+        self.token_identifier == EGLD_TOKEN_ID
+    }
+
+    /// Numbers as decimal strings, since amounts do not fit JSON integers.
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("token", self.token_identifier.as_str().into()),
+            ("nonce", self.nonce.to_string().into()),
+            ("amount", self.amount.to_string().into()),
+        ])
+    }
+
+    /// Inverse of `to_json`.
+    pub fn from_json(json: &JsonValue) -> Option<Payment> {
+        Some(Payment {
+            token_identifier: json.get("token")?.as_str()?.to_string(),
+            nonce: json.get("nonce")?.as_str()?.parse().ok()?,
+            amount: json.get("amount")?.as_str()?.parse().ok()?,
+        })
+    }
+}
+
+/// Amount and token, with the nonce in hex as in `TOKEN-123456-0a`.
+impl fmt::Display for Payment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.token_identifier)?;
+        if self.nonce != 0 {
+            // Whole bytes, e.g. `-01`, `-0100`.
+            let digits = format!("{:x}", self.nonce);
+            let padding = if digits.len().is_multiple_of(2) {
+                ""
+            } else {
+                "0"
+            };
+            write!(f, "-{padding}{digits}")?;
+        }
+        Ok(())
+    }
+}
+
+/// All the tokens the call sent back, EGLD included, in transfer order.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsBackTransfers;
+
+impl<Original> RetListItem<Original> for ReturnsBackTransfers {
+    type Returns = Vec<Payment>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        raw.transfers.to_vec()
+    }
+}
+
+/// The total EGLD sent back.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsEgldBack;
+
+impl<Original> RetListItem<Original> for ReturnsEgldBack {
+    type Returns = BigUint;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        raw.transfers
+            .iter()
+            .filter(|payment| payment.is_egld())
+            .fold(BigUint::zero(), |total, payment| &total + &payment.amount)
+    }
+}
+
+/// The transfers of one token sent back, all nonces.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsEsdtBack(pub Cow<'static, str>);
+
+impl ReturnsEsdtBack {
+    pub fn new(token_identifier: impl Into<Cow<'static, str>>) -> Self {
+        ReturnsEsdtBack(token_identifier.into())
+    }
+}
+
+impl<Original> RetListItem<Original> for ReturnsEsdtBack {
+    type Returns = Vec<Payment>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        raw.transfers
+            .iter()
+            .filter(|payment| payment.token_identifier == self.0)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Checks that the call sent back the given payment. Panics otherwise.
+///
+/// Other transfers are allowed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpectTransfer(pub Payment);
+
+impl<Original> RetListItem<Original> for ExpectTransfer {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        if let Err(err) = Expectation::Transfer(self.0.clone()).check(raw) {
+            panic!("ExpectTransfer: {err}");
+        }
+    }
+
+    fn expectation(&self) -> Option<Expectation> {
+        Some(Expectation::Transfer(self.0.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        big_num::ReturnsBigUint,
+        mock_vm::{MockVm, TxCall},
+        prototype_v6::*,
+    };
+
+    fn swap_vm() -> MockVm {
+        let mut vm = MockVm::new();
+        vm.register("swap", |_args| {
+            Ok(RawResult::default()
+                .with_out(1u32)
+                .with_transfer(Payment::esdt("WEGLD-bd4d79", 0, 500u32))
+                .with_transfer(Payment::egld(10u32))
+                .with_transfer(Payment::esdt("LKMEX-aab910", 3, u128::MAX))
+                .with_transfer(Payment::egld(5u32))
+                .with_transfer(Payment::esdt("WEGLD-bd4d79", 0, 20u32)))
+        });
+        vm
+    }
+
+    #[test]
+    fn test_back_transfers() {
+        let raw = swap_vm().execute(&TxCall::new("swap")).unwrap();
+        let list = new_list()
+            .original_marker::<u32>()
+            .returns(ReturnsEgldBack)
+            .handle_result(ExpectTransfer(Payment::esdt("LKMEX-aab910", 3, u128::MAX)))
+            .returns(ReturnsEsdtBack::new("WEGLD-bd4d79"))
+            .returns(ReturnsBackTransfers)
+            .returns(ReturnsBigUint);
+        let (egld, wegld, all, out) = list.eval(&raw);
+        assert_eq!(egld, BigUint::from(15u8));
+        assert_eq!(
+            wegld,
+            vec![
+                Payment::esdt("WEGLD-bd4d79", 0, 500u32),
+                Payment::esdt("WEGLD-bd4d79", 0, 20u32),
+            ]
+        );
+        assert_eq!(all.len(), 5);
+        assert_eq!(out, BigUint::from(1u8));
+
+        let list = new_list()
+            .original_marker::<u32>()
+            .returns(ReturnsEgldBack)
+            .returns(ReturnsEsdtBack::new("WEGLD-bd4d79"));
+        assert_eq!(list.eval(&RawResult::default()), (BigUint::zero(), vec![]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ExpectTransfer: expected transfer 7 LKMEX-aab910-03")]
+    fn test_expect_transfer_missing() {
+        let raw = swap_vm().execute(&TxCall::new("swap")).unwrap();
+        let list = new_list()
+            .original_marker::<u32>()
+            .handle_result(ExpectTransfer(Payment::esdt("LKMEX-aab910", 3, 7u8)));
+        list.eval(&raw);
+    }
+
+    #[test]
+    fn test_payment_json() {
+        let payment = Payment::esdt("LKMEX-aab910", 3, u128::MAX);
+        assert_eq!(
+            payment.to_json().to_string(),
+            r#"{"token":"LKMEX-aab910","nonce":"3","amount":"340282366920938463463374607431768211455"}"#
+        );
+        assert_eq!(Payment::from_json(&payment.to_json()), Some(payment));
+        assert_eq!(Payment::egld(1u8).to_string(), "1 EGLD");
+        assert_eq!(
+            Payment::esdt("LKMEX-aab910", 3, 7u8).to_string(),
+            "7 LKMEX-aab910-03"
+        );
+        assert_eq!(
+            Payment::esdt("LKMEX-aab910", 0x1a2, 7u8).to_string(),
+            "7 LKMEX-aab910-01a2"
+        );
+    }
+}
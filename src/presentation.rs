@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+
+use crate::{
+    deploy::{Address, ManagedAddress},
+    encoding::{base64_encode, bech32_address, hex_encode, utf8_decode, EncodingError},
+    prototype_v6::RetListItem,
+    raw_result::RawResult,
+};
+
+fn single_out_or_panic<'a>(raw: &'a RawResult, handler: &str) -> &'a [u8] {
+    raw.single_out()
+        .unwrap_or_else(|err| panic!("{handler}: {err}"))
+}
+
+/// The result bytes as lowercase hex, without prefix.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsHex;
+
+impl<Original> RetListItem<Original> for ReturnsHex {
+    type Returns = String;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        hex_encode(single_out_or_panic(raw, "ReturnsHex"))
+    }
+}
+
+/// The result bytes in standard, padded base64.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsBase64;
+
+impl<Original> RetListItem<Original> for ReturnsBase64 {
+    type Returns = String;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        base64_encode(single_out_or_panic(raw, "ReturnsBase64"))
+    }
+}
+
+/// Result types holding an account address, for `ReturnsBech32Address`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not an address result",
+    note = "`ReturnsBech32Address` needs the type given to `original_marker` to be an address"
+)]
+pub trait AddressResult {}
+
+impl AddressResult for Address {}
+
+impl AddressResult for ManagedAddress {}
+
+/// An address result in bech32, with the given human-readable prefix, e.g. `"erd"`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsBech32Address(pub Cow<'static, str>);
+
+impl ReturnsBech32Address {
+    pub fn new(hrp: impl Into<Cow<'static, str>>) -> Self {
+        ReturnsBech32Address(hrp.into())
+    }
+}
+
+impl<Original: AddressResult> RetListItem<Original> for ReturnsBech32Address {
+    type Returns = Result<String, EncodingError>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        bech32_address(&self.0, single_out_or_panic(raw, "ReturnsBech32Address"))
+    }
+}
+
+/// The result bytes as text, or the position of the first invalid UTF-8 sequence.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsUtf8String;
+
+impl<Original> RetListItem<Original> for ReturnsUtf8String {
+    type Returns = Result<String, EncodingError>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        utf8_decode(single_out_or_panic(raw, "ReturnsUtf8String"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prototype_v6::*;
+
+    #[test]
+    fn test_presentation() {
+        let list = new_list()
+            .original_marker::<Vec<u8>>()
+            .returns(ReturnsHex)
+            .returns(ReturnsBase64)
+            .returns(ReturnsUtf8String)
+            .returns(ReturnsDefault);
+        let raw = RawResult::default().with_out(b"hi!".to_vec());
+        assert_eq!(
+            list.eval(&raw),
+            (
+                "686921".to_string(),
+                "aGkh".to_string(),
+                Ok("hi!".to_string()),
+                Vec::new()
+            )
+        );
+
+        let list = new_list()
+            .original_marker::<Address>()
+            .returns(ReturnsBech32Address::new("erd"));
+        let raw = RawResult::default().with_out(vec![0u8; 32]);
+        assert_eq!(
+            list.eval(&raw),
+            Ok("erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let list = new_list()
+            .original_marker::<Vec<u8>>()
+            .returns(ReturnsUtf8String);
+        assert_eq!(
+            list.eval(&RawResult::default().with_out(vec![b'o', b'k', 0xc3])),
+            Err(EncodingError::InvalidUtf8 { valid_up_to: 2 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_address() {
+        let list = new_list()
+            .original_marker::<ManagedAddress>()
+            .returns(ReturnsBech32Address::new("erd"))
+            .returns(ReturnsBech32Address::new(String::new()));
+        assert_eq!(
+            list.eval(&RawResult::default().with_out(vec![1u8, 2, 3])),
+            (
+                Err(EncodingError::InvalidAddressLength(3)),
+                Err(EncodingError::InvalidAddressLength(3))
+            )
+        );
+        assert_eq!(
+            list.eval(&RawResult::default().with_out(vec![1u8; 32])).1,
+            Err(EncodingError::InvalidHrp(String::new()))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ReturnsHex: too many results")]
+    fn test_multiple_results() {
+        let list = new_list().original_marker::<Vec<u8>>().returns(ReturnsHex);
+        list.eval(&RawResult::default().with_out(1u8).with_out(2u8));
+    }
+}
@@ -1,3 +1,4 @@
+pub use crate::flat_tuples::TupleConcat;
 use crate::flat_tuples::{TupleFlatPush, TupleUnpack};
 
 pub trait Returns {
@@ -141,42 +142,36 @@ pub fn example() {
 
 /////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Joins a pair of flat tuples into one.
+///
+/// Both sides must be flat tuples: `(x, ())` joins to `x` only if `x` is itself a tuple,
+/// and `((), (x,))` joins to `(x,)`, not to `x`.
 pub trait Join {
     type Joined;
 
     fn join(self) -> Self::Joined;
 }
 
-impl<T> Join for (T, (())) {
-    type Joined = T;
+impl<A, B> Join for (A, B)
+where
+    A: TupleConcat<B>,
+{
+    type Joined = A::Output;
 
     fn join(self) -> Self::Joined {
-        self.0
+        self.0.flat_concat(self.1)
     }
 }
 
-impl<T> Join for ((), (T,)) {
-    type Joined = T;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    fn join(self) -> Self::Joined {
-        self.1 .0
+    #[test]
+    fn test_join() {
+        assert_eq!(((1, "a"), ()).join(), (1, "a"));
+        assert_eq!(((), (5,)).join(), (5,));
+        assert_eq!(((1,), (2, 3)).join(), (1, 2, 3));
+        assert_eq!(((), ()).join(), ());
     }
 }
-
-/////////////////////////////////////////////////////////////////////////////////////////////
-
-pub trait TupleConcat<Other> {
-    type Output;
-}
-
-impl<T> TupleConcat<()> for T {
-    type Output = T;
-}
-
-impl<A, X> TupleConcat<(X,)> for (A,) {
-    type Output = (A, X);
-}
-
-impl<A, B, X> TupleConcat<(X,)> for (A, B) {
-    type Output = (A, B, X);
-}
@@ -1,93 +1,343 @@
-use std::marker::PhantomData;
+use std::{borrow::Cow, cell::RefCell, marker::PhantomData, rc::Rc};
+
+use crate::{
+    codec::{DecodeError, MultiValueEncoded, TopDecode, TopDecodeIter, TopDecodeMulti},
+    expectations::Expectation,
+    nested_tuples::{
+        Flatten, NestedTuple, NestedTupleAppend, NestedTupleColumns, NestedTupleSplice,
+    },
+    raw_result::RawResult,
+};
+
+/// A result handler, for results of type `Original`.
+///
+/// `Kind` limits the handler to lists of one kind of transaction, e.g. `Deploy`.
+/// Most handlers work for any kind and keep the default.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot handle results of type `{Original}`",
+    label = "not a result handler for `{Original}`"
+)]
+pub trait RetListItem<Original, Kind = AnyKind> {
+    type Returns;
 
-use crate::nested_tuples::{Flatten, NestedTupleAppend};
+    fn single_return(&self, raw: &RawResult) -> Self::Returns;
 
-pub trait RetListItem<Original> {
-    type Returns;
+    /// What the handler checks, if anything, as data that can be recorded and replayed.
+    fn expectation(&self) -> Option<Expectation> {
+        None
+    }
 
-    fn single_return(&self) -> Self::Returns;
+    /// Name of the output, if the handler was given one.
+    fn label(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl<Original> RetListItem<Original> for () {
     type Returns = ();
 
-    fn single_return(&self) -> Self::Returns {}
+    fn single_return(&self, _raw: &RawResult) -> Self::Returns {}
 }
 
-pub struct PrintMessage(&'static str);
+/// Prints a message. Built with `PrintMessage::new("x")`, or `PrintMessage("x".into())`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintMessage(pub Cow<'static, str>);
+
+impl PrintMessage {
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        PrintMessage(message.into())
+    }
+}
 
 impl<Original> RetListItem<Original> for PrintMessage {
     type Returns = ();
 
-    fn single_return(&self) -> Self::Returns {
+    fn single_return(&self, _raw: &RawResult) -> Self::Returns {
         println!("{}", self.0)
     }
 }
 
-pub struct ReturnsStr(&'static str);
+/// Returns the given string. Built with `ReturnsStr::new("x")`, or `ReturnsStr("x".into())`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsStr(pub Cow<'static, str>);
+
+impl ReturnsStr {
+    pub fn new(s: impl Into<Cow<'static, str>>) -> Self {
+        ReturnsStr(s.into())
+    }
+}
 
 impl RetListItem<i32> for ReturnsStr {
-    type Returns = &'static str;
+    type Returns = Cow<'static, str>;
 
-    fn single_return(&self) -> Self::Returns {
+    fn single_return(&self, _raw: &RawResult) -> Self::Returns {
         println!("returning str: {} ...", self.0);
-        self.0
+        self.0.clone()
     }
 }
 
-pub struct ReturnsInt(i32);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnsInt(pub i32);
 
 impl RetListItem<i32> for ReturnsInt {
     type Returns = i32;
 
-    fn single_return(&self) -> Self::Returns {
+    fn single_return(&self, _raw: &RawResult) -> Self::Returns {
         println!("returning i32: {} ...", self.0);
         self.0
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReturnsDefault;
 
 impl<D: Default> RetListItem<D> for ReturnsDefault {
     type Returns = D;
 
-    fn single_return(&self) -> Self::Returns {
+    fn single_return(&self, _raw: &RawResult) -> Self::Returns {
         D::default()
     }
 }
 
+/// Decodes the single result into a caller-provided slot instead of returning it.
+pub struct ReturnsInto<'a, T>(RefCell<&'a mut T>);
+
+impl<'a, T> ReturnsInto<'a, T> {
+    pub fn new(slot: &'a mut T) -> Self {
+        ReturnsInto(RefCell::new(slot))
+    }
+}
+
+impl<'a, T: TopDecode> RetListItem<T> for ReturnsInto<'a, T> {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        **self.0.borrow_mut() = decode_into(raw, "ReturnsInto");
+    }
+}
+
+fn decode_into<T: TopDecode>(raw: &RawResult, handler: &str) -> T {
+    raw.single_out()
+        .and_then(T::top_decode)
+        .unwrap_or_else(|err| panic!("{handler}: {err}"))
+}
+
+/// Same as `ReturnsInto`, but the slot is shared, so it can outlive the list.
+pub struct ReturnsIntoCell<T>(pub Rc<RefCell<T>>);
+
+impl<T: TopDecode> RetListItem<T> for ReturnsIntoCell<T> {
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        *self.0.borrow_mut() = decode_into(raw, "ReturnsIntoCell");
+    }
+}
+
+/// Decodes a multi-value result, described by a flat tuple such as `(A, B, C)`.
+///
+/// Returns the parts as a nested tuple, so that `ListWrapper::returns_multi`
+/// can contribute each of them as a separate output.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct ReturnsMultiValue<T>(PhantomData<T>);
+
+impl<T> Default for ReturnsMultiValue<T> {
+    fn default() -> Self {
+        ReturnsMultiValue(PhantomData)
+    }
+}
+
+impl<T: TopDecodeMulti> RetListItem<T> for ReturnsMultiValue<T> {
+    type Returns = T::Nested;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        T::multi_decode(&raw.out).unwrap_or_else(|err| panic!("ReturnsMultiValue: {err}"))
+    }
+}
+
+/// Lazily decodes the items of a `MultiValueEncoded<T>` result.
+///
+/// Nothing is decoded until the returned iterator is advanced.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct ReturnsIter<T>(PhantomData<T>);
+
+impl<T> Default for ReturnsIter<T> {
+    fn default() -> Self {
+        ReturnsIter(PhantomData)
+    }
+}
+
+fn expect_item<T>(item: Result<T, DecodeError>) -> T {
+    item.unwrap_or_else(|err| panic!("ReturnsIter: {err}"))
+}
+
+impl<T: TopDecode> RetListItem<MultiValueEncoded<T>> for ReturnsIter<T> {
+    type Returns = std::iter::Map<TopDecodeIter<T>, fn(Result<T, DecodeError>) -> T>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        TopDecodeIter::new(raw.out.clone()).map(expect_item as fn(_) -> T)
+    }
+}
+
+/// Decodes all items of a `MultiValueEncoded<T>` result into a `Vec`.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct ReturnsVec<T>(PhantomData<T>);
+
+impl<T> Default for ReturnsVec<T> {
+    fn default() -> Self {
+        ReturnsVec(PhantomData)
+    }
+}
+
+impl<T: TopDecode> RetListItem<MultiValueEncoded<T>> for ReturnsVec<T> {
+    type Returns = Vec<T>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        ReturnsIter::<T>::default().single_return(raw).collect()
+    }
+}
+
+/// Gives a name to the output of a handler, otherwise unchanged.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Labelled<H> {
+    pub label: Cow<'static, str>,
+    pub handler: H,
+}
+
+impl<H> Labelled<H> {
+    pub fn new(label: impl Into<Cow<'static, str>>, handler: H) -> Self {
+        Labelled {
+            label: label.into(),
+            handler,
+        }
+    }
+}
+
+impl<Original, K, H: RetListItem<Original, K>> RetListItem<Original, K> for Labelled<H> {
+    type Returns = H::Returns;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        self.handler.single_return(raw)
+    }
+
+    fn expectation(&self) -> Option<Expectation> {
+        self.handler.expectation()
+    }
+
+    fn label(&self) -> Option<&str> {
+        Some(&self.label)
+    }
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////////
 
 pub trait RetList {
     type Original;
-    type ListReturn;
+    type Kind: TxKind;
+    type ListReturn: NestedTuple;
+
+    /// Number of handlers in the list, returning or not.
+    const HANDLER_COUNT: usize;
 
-    fn list_return(&self) -> Self::ListReturn;
+    /// Runs the handlers in the order they were pushed.
+    ///
+    /// The head of each cons node is the earlier handler, so it runs before the tail.
+    fn list_return(&self, raw: &RawResult) -> Self::ListReturn;
+
+    /// Runs the handlers last-pushed first. The output has the same layout as with `list_return`.
+    fn list_return_reverse(&self, raw: &RawResult) -> Self::ListReturn;
+}
+
+/// Order in which the side effects of the handlers happen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EvalOrder {
+    /// In the order the handlers were pushed.
+    #[default]
+    InOrder,
+    Reverse,
 }
 
-pub trait RetListAppendRet<T>: RetList
+#[diagnostic::on_unimplemented(
+    message = "`{T}` cannot be added to this list as a returning handler",
+    note = "`{T}` must implement `RetListItem` for the type given to `original_marker`"
+)]
+pub trait RetListAppendRet<T, Kind = AnyKind>: RetList
 where
-    T: RetListItem<Self::Original>,
+    T: RetListItem<Self::Original, Kind>,
 {
-    type RetOutput: RetList<Original = Self::Original>;
+    type RetOutput: RetList<Original = Self::Original, Kind = Self::Kind>;
 
     fn append_ret(self, t: T) -> Self::RetOutput;
 }
 
-pub trait RetListAppendNoRet<T>: RetList
+#[diagnostic::on_unimplemented(
+    message = "`{T}` cannot be added to this list via `returns_multi`",
+    note = "`{T}` must implement `RetListItem` for the type given to `original_marker`, returning a nested tuple"
+)]
+pub trait RetListAppendRetMulti<T, Kind = AnyKind>: RetList
 where
-    T: RetListItem<Self::Original, Returns = ()>,
+    T: RetListItem<Self::Original, Kind>,
 {
-    type NoRetOutput: RetList<Original = Self::Original>;
+    type RetMultiOutput: RetList<Original = Self::Original, Kind = Self::Kind>;
+
+    fn append_ret_multi(self, t: T) -> Self::RetMultiOutput;
+}
+
+#[diagnostic::on_unimplemented(
+    message = "`{T}` cannot be added to this list via `handle_result`",
+    note = "`{T}` must implement `RetListItem` for the type given to `original_marker`, and return `()`",
+    note = "use `returns` for handlers that produce a value"
+)]
+pub trait RetListAppendNoRet<T, Kind = AnyKind>: RetList
+where
+    T: RetListItem<Self::Original, Kind, Returns: UnitReturns>,
+{
+    type NoRetOutput: RetList<Original = Self::Original, Kind = Self::Kind>;
 
     fn append_no_ret(self, t: T) -> Self::NoRetOutput;
 }
 
+/// Implemented only by `()`, so that `handle_result` can explain why a handler returning a value
+/// is rejected, instead of reporting a bare type mismatch.
+///
+/// Sealed, so that no other type can be dropped silently by `handle_result`.
+#[diagnostic::on_unimplemented(
+    message = "`handle_result` needs a handler that returns `()`, but this one returns `{Self}`",
+    label = "returns `{Self}`",
+    note = "use `returns` for handlers that produce a value"
+)]
+pub trait UnitReturns: sealed::Sealed {}
+
+impl UnitReturns for () {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for () {}
+}
+
 impl RetList for () {
     type Original = ();
+    type Kind = Call;
     type ListReturn = ();
 
-    fn list_return(&self) -> Self::ListReturn {}
+    const HANDLER_COUNT: usize = 0;
+
+    fn list_return(&self, _raw: &RawResult) -> Self::ListReturn {}
+
+    fn list_return_reverse(&self, _raw: &RawResult) -> Self::ListReturn {}
 }
 
 impl<T> RetListAppendRet<T> for ()
@@ -97,26 +347,84 @@ where
     type RetOutput = ConsRet<T, ()>;
 
     fn append_ret(self, t: T) -> Self::RetOutput {
-        ConsRet { head: t, tail: () }
+        ConsRet {
+            head: t,
+            tail: (),
+            _kind: PhantomData,
+        }
     }
 }
 
 impl<T> RetListAppendNoRet<T> for ()
 where
-    T: RetListItem<(), Returns = ()>,
+    T: RetListItem<(), Returns: UnitReturns>,
 {
     type NoRetOutput = ConsNoRet<T, ()>;
 
     fn append_no_ret(self, t: T) -> Self::NoRetOutput {
-        ConsNoRet { head: t, tail: () }
+        ConsNoRet {
+            head: t,
+            tail: (),
+            _kind: PhantomData,
+        }
     }
 }
 
-pub struct OriginalMarker<O> {
-    _phantom: PhantomData<O>,
+impl<T> RetListAppendRetMulti<T> for ()
+where
+    T: RetListItem<()>,
+    T::Returns: NestedTupleSplice<()>,
+{
+    type RetMultiOutput = ConsRetMulti<T, ()>;
+
+    fn append_ret_multi(self, t: T) -> Self::RetMultiOutput {
+        ConsRetMulti {
+            head: t,
+            tail: (),
+            _kind: PhantomData,
+        }
+    }
 }
 
-impl<O> Default for OriginalMarker<O> {
+/// Kind of transaction that produced the results, given to `OriginalMarker`.
+pub trait TxKind {}
+
+/// Endpoint call.
+pub struct Call;
+
+/// Contract deploy, which also produces the address of the new contract.
+pub struct Deploy;
+
+/// Contract upgrade. Only the status and the results of the upgrade endpoint.
+pub struct Upgrade;
+
+impl TxKind for Call {}
+
+impl TxKind for Deploy {}
+
+impl TxKind for Upgrade {}
+
+/// The default `Kind` of `RetListItem`, for handlers that work with any kind of transaction.
+pub struct AnyKind;
+
+/// Lists for transactions of kind `Self` accept handlers limited to `Kind`.
+///
+/// Each kind accepts handlers for any kind, and those limited to itself.
+#[diagnostic::on_unimplemented(
+    message = "handlers limited to `{Kind}` transactions cannot be used in `{Self}` lists",
+    label = "only for `{Kind}` lists"
+)]
+pub trait AcceptsHandlers<Kind> {}
+
+impl<K: TxKind> AcceptsHandlers<AnyKind> for K {}
+
+impl<K: TxKind> AcceptsHandlers<K> for K {}
+
+pub struct OriginalMarker<O, K = Call> {
+    _phantom: PhantomData<(O, K)>,
+}
+
+impl<O, K> Default for OriginalMarker<O, K> {
     fn default() -> Self {
         Self {
             _phantom: Default::default(),
@@ -124,154 +432,335 @@ impl<O> Default for OriginalMarker<O> {
     }
 }
 
-impl<O> RetList for OriginalMarker<O> {
+impl<O, K: TxKind> RetList for OriginalMarker<O, K> {
     type Original = O;
+    type Kind = K;
     type ListReturn = ();
 
-    fn list_return(&self) -> Self::ListReturn {}
+    const HANDLER_COUNT: usize = 0;
+
+    fn list_return(&self, _raw: &RawResult) -> Self::ListReturn {}
+
+    fn list_return_reverse(&self, _raw: &RawResult) -> Self::ListReturn {}
 }
 
-impl<O, T> RetListAppendRet<T> for OriginalMarker<O>
+impl<O, K, T, TK> RetListAppendRet<T, TK> for OriginalMarker<O, K>
 where
-    T: RetListItem<O>,
+    K: TxKind + AcceptsHandlers<TK>,
+    T: RetListItem<O, TK>,
 {
-    type RetOutput = ConsRet<T, OriginalMarker<O>>;
+    type RetOutput = ConsRet<T, OriginalMarker<O, K>, TK>;
 
     fn append_ret(self, t: T) -> Self::RetOutput {
         ConsRet {
             head: t,
             tail: self,
+            _kind: PhantomData,
         }
     }
 }
 
-impl<O, T> RetListAppendNoRet<T> for OriginalMarker<O>
+impl<O, K, T, TK> RetListAppendNoRet<T, TK> for OriginalMarker<O, K>
 where
-    T: RetListItem<O, Returns = ()>,
+    K: TxKind + AcceptsHandlers<TK>,
+    T: RetListItem<O, TK, Returns: UnitReturns>,
 {
-    type NoRetOutput = ConsNoRet<T, OriginalMarker<O>>;
+    type NoRetOutput = ConsNoRet<T, OriginalMarker<O, K>, TK>;
 
     fn append_no_ret(self, t: T) -> Self::NoRetOutput {
         ConsNoRet {
             head: t,
             tail: self,
+            _kind: PhantomData,
+        }
+    }
+}
+
+impl<O, K, T, TK> RetListAppendRetMulti<T, TK> for OriginalMarker<O, K>
+where
+    K: TxKind + AcceptsHandlers<TK>,
+    T: RetListItem<O, TK>,
+    T::Returns: NestedTupleSplice<()>,
+{
+    type RetMultiOutput = ConsRetMulti<T, OriginalMarker<O, K>, TK>;
+
+    fn append_ret_multi(self, t: T) -> Self::RetMultiOutput {
+        ConsRetMulti {
+            head: t,
+            tail: self,
+            _kind: PhantomData,
         }
     }
 }
 
-pub struct ConsRet<Head, Tail>
+pub struct ConsRet<Head, Tail, HK = AnyKind>
 where
-    Head: RetListItem<Tail::Original>,
+    Head: RetListItem<Tail::Original, HK>,
     Tail: RetList,
 {
-    head: Head,
-    tail: Tail,
+    pub(crate) head: Head,
+    pub(crate) tail: Tail,
+    pub(crate) _kind: PhantomData<HK>,
 }
 
-impl<Head, Tail> RetList for ConsRet<Head, Tail>
+impl<Head, Tail, HK> RetList for ConsRet<Head, Tail, HK>
 where
-    Head: RetListItem<Tail::Original>,
+    Head: RetListItem<Tail::Original, HK>,
     Tail: RetList,
 {
     type Original = Tail::Original;
+    type Kind = Tail::Kind;
     type ListReturn = (Head::Returns, Tail::ListReturn);
 
-    fn list_return(&self) -> Self::ListReturn {
-        let head_ret = self.head.single_return();
-        let tail_ret = self.tail.list_return();
+    const HANDLER_COUNT: usize = Tail::HANDLER_COUNT + 1;
+
+    fn list_return(&self, raw: &RawResult) -> Self::ListReturn {
+        let head_ret = self.head.single_return(raw);
+        let tail_ret = self.tail.list_return(raw);
+        (head_ret, tail_ret)
+    }
+
+    fn list_return_reverse(&self, raw: &RawResult) -> Self::ListReturn {
+        let tail_ret = self.tail.list_return_reverse(raw);
+        let head_ret = self.head.single_return(raw);
         (head_ret, tail_ret)
     }
 }
 
-impl<Head, Tail, T> RetListAppendRet<T> for ConsRet<Head, Tail>
+impl<Head, Tail, HK, T, TK> RetListAppendRet<T, TK> for ConsRet<Head, Tail, HK>
 where
-    Head: RetListItem<Tail::Original>,
-    Tail: RetList + RetListAppendRet<T>,
-    T: RetListItem<Tail::Original>,
+    Head: RetListItem<Tail::Original, HK>,
+    Tail: RetList + RetListAppendRet<T, TK>,
+    T: RetListItem<Tail::Original, TK>,
 {
-    type RetOutput = ConsRet<Head, <Tail as RetListAppendRet<T>>::RetOutput>;
+    type RetOutput = ConsRet<Head, <Tail as RetListAppendRet<T, TK>>::RetOutput, HK>;
 
     fn append_ret(self, t: T) -> Self::RetOutput {
         ConsRet {
             head: self.head,
             tail: self.tail.append_ret(t),
+            _kind: PhantomData,
         }
     }
 }
 
-impl<Head, Tail, T> RetListAppendNoRet<T> for ConsRet<Head, Tail>
+impl<Head, Tail, HK, T, TK> RetListAppendNoRet<T, TK> for ConsRet<Head, Tail, HK>
 where
-    Head: RetListItem<Tail::Original>,
-    Tail: RetList + RetListAppendNoRet<T>,
-    T: RetListItem<Tail::Original, Returns = ()>,
+    Head: RetListItem<Tail::Original, HK>,
+    Tail: RetList + RetListAppendNoRet<T, TK>,
+    T: RetListItem<Tail::Original, TK, Returns: UnitReturns>,
 {
-    type NoRetOutput = ConsRet<Head, <Tail as RetListAppendNoRet<T>>::NoRetOutput>;
+    type NoRetOutput = ConsRet<Head, <Tail as RetListAppendNoRet<T, TK>>::NoRetOutput, HK>;
 
     fn append_no_ret(self, t: T) -> Self::NoRetOutput {
         ConsRet {
             head: self.head,
             tail: self.tail.append_no_ret(t),
+            _kind: PhantomData,
+        }
+    }
+}
+
+impl<Head, Tail, HK, T, TK> RetListAppendRetMulti<T, TK> for ConsRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Tail: RetList + RetListAppendRetMulti<T, TK>,
+    T: RetListItem<Tail::Original, TK>,
+{
+    type RetMultiOutput = ConsRet<Head, <Tail as RetListAppendRetMulti<T, TK>>::RetMultiOutput, HK>;
+
+    fn append_ret_multi(self, t: T) -> Self::RetMultiOutput {
+        ConsRet {
+            head: self.head,
+            tail: self.tail.append_ret_multi(t),
+            _kind: PhantomData,
         }
     }
 }
 
 /// Handlers that return nothing.
-pub struct ConsNoRet<Head, Tail>
+pub struct ConsNoRet<Head, Tail, HK = AnyKind>
 where
-    Head: RetListItem<Tail::Original, Returns = ()>,
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
     Tail: RetList,
 {
-    head: Head,
-    tail: Tail,
+    pub(crate) head: Head,
+    pub(crate) tail: Tail,
+    pub(crate) _kind: PhantomData<HK>,
 }
 
-impl<Head, Tail> RetList for ConsNoRet<Head, Tail>
+impl<Head, Tail, HK> RetList for ConsNoRet<Head, Tail, HK>
 where
-    Head: RetListItem<Tail::Original, Returns = ()>,
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
     Tail: RetList,
 {
     type Original = Tail::Original;
+    type Kind = Tail::Kind;
     type ListReturn = Tail::ListReturn;
 
-    fn list_return(&self) -> Self::ListReturn {
-        self.head.single_return();
-        self.tail.list_return()
+    const HANDLER_COUNT: usize = Tail::HANDLER_COUNT + 1;
+
+    fn list_return(&self, raw: &RawResult) -> Self::ListReturn {
+        self.head.single_return(raw);
+        self.tail.list_return(raw)
+    }
+
+    fn list_return_reverse(&self, raw: &RawResult) -> Self::ListReturn {
+        let tail_ret = self.tail.list_return_reverse(raw);
+        self.head.single_return(raw);
+        tail_ret
     }
 }
 
-impl<Head, Tail, T> RetListAppendRet<T> for ConsNoRet<Head, Tail>
+impl<Head, Tail, HK, T, TK> RetListAppendRet<T, TK> for ConsNoRet<Head, Tail, HK>
 where
-    Head: RetListItem<Tail::Original, Returns = ()>,
-    Tail: RetList + RetListAppendRet<T>,
-    T: RetListItem<Tail::Original>,
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
+    Tail: RetList + RetListAppendRet<T, TK>,
+    T: RetListItem<Tail::Original, TK>,
 {
-    type RetOutput = ConsNoRet<Head, <Tail as RetListAppendRet<T>>::RetOutput>;
+    type RetOutput = ConsNoRet<Head, <Tail as RetListAppendRet<T, TK>>::RetOutput, HK>;
 
     fn append_ret(self, t: T) -> Self::RetOutput {
         ConsNoRet {
             head: self.head,
             tail: self.tail.append_ret(t),
+            _kind: PhantomData,
         }
     }
 }
 
-impl<Head, Tail, T> RetListAppendNoRet<T> for ConsNoRet<Head, Tail>
+impl<Head, Tail, HK, T, TK> RetListAppendNoRet<T, TK> for ConsNoRet<Head, Tail, HK>
 where
-    Head: RetListItem<Tail::Original, Returns = ()>,
-    Tail: RetList + RetListAppendNoRet<T>,
-    T: RetListItem<Tail::Original, Returns = ()>,
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
+    Tail: RetList + RetListAppendNoRet<T, TK>,
+    T: RetListItem<Tail::Original, TK, Returns: UnitReturns>,
 {
-    type NoRetOutput = ConsNoRet<Head, <Tail as RetListAppendNoRet<T>>::NoRetOutput>;
+    type NoRetOutput = ConsNoRet<Head, <Tail as RetListAppendNoRet<T, TK>>::NoRetOutput, HK>;
 
     fn append_no_ret(self, t: T) -> Self::NoRetOutput {
         ConsNoRet {
             head: self.head,
             tail: self.tail.append_no_ret(t),
+            _kind: PhantomData,
         }
     }
 }
 
-pub struct ListWrapper<L: RetList>(L);
+impl<Head, Tail, HK, T, TK> RetListAppendRetMulti<T, TK> for ConsNoRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
+    Tail: RetList + RetListAppendRetMulti<T, TK>,
+    T: RetListItem<Tail::Original, TK>,
+{
+    type RetMultiOutput =
+        ConsNoRet<Head, <Tail as RetListAppendRetMulti<T, TK>>::RetMultiOutput, HK>;
+
+    fn append_ret_multi(self, t: T) -> Self::RetMultiOutput {
+        ConsNoRet {
+            head: self.head,
+            tail: self.tail.append_ret_multi(t),
+            _kind: PhantomData,
+        }
+    }
+}
+
+/// Handlers that return several values at once, as a nested tuple.
+/// Each value becomes a separate element of the list output.
+pub struct ConsRetMulti<Head, Tail, HK = AnyKind>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn>,
+    Tail: RetList,
+{
+    pub(crate) head: Head,
+    pub(crate) tail: Tail,
+    pub(crate) _kind: PhantomData<HK>,
+}
+
+impl<Head, Tail, HK> RetList for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn>,
+    Tail: RetList,
+{
+    type Original = Tail::Original;
+    type Kind = Tail::Kind;
+    type ListReturn = <Head::Returns as NestedTupleSplice<Tail::ListReturn>>::Output;
+
+    const HANDLER_COUNT: usize = Tail::HANDLER_COUNT + 1;
+
+    fn list_return(&self, raw: &RawResult) -> Self::ListReturn {
+        let head_ret = self.head.single_return(raw);
+        let tail_ret = self.tail.list_return(raw);
+        head_ret.splice(tail_ret)
+    }
+
+    fn list_return_reverse(&self, raw: &RawResult) -> Self::ListReturn {
+        let tail_ret = self.tail.list_return_reverse(raw);
+        let head_ret = self.head.single_return(raw);
+        head_ret.splice(tail_ret)
+    }
+}
+
+impl<Head, Tail, HK, T, TK> RetListAppendRet<T, TK> for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn>
+        + NestedTupleSplice<<Tail::RetOutput as RetList>::ListReturn>,
+    Tail: RetList + RetListAppendRet<T, TK>,
+    T: RetListItem<Tail::Original, TK>,
+{
+    type RetOutput = ConsRetMulti<Head, <Tail as RetListAppendRet<T, TK>>::RetOutput, HK>;
+
+    fn append_ret(self, t: T) -> Self::RetOutput {
+        ConsRetMulti {
+            head: self.head,
+            tail: self.tail.append_ret(t),
+            _kind: PhantomData,
+        }
+    }
+}
+
+impl<Head, Tail, HK, T, TK> RetListAppendNoRet<T, TK> for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn>
+        + NestedTupleSplice<<Tail::NoRetOutput as RetList>::ListReturn>,
+    Tail: RetList + RetListAppendNoRet<T, TK>,
+    T: RetListItem<Tail::Original, TK, Returns: UnitReturns>,
+{
+    type NoRetOutput = ConsRetMulti<Head, <Tail as RetListAppendNoRet<T, TK>>::NoRetOutput, HK>;
+
+    fn append_no_ret(self, t: T) -> Self::NoRetOutput {
+        ConsRetMulti {
+            head: self.head,
+            tail: self.tail.append_no_ret(t),
+            _kind: PhantomData,
+        }
+    }
+}
+
+impl<Head, Tail, HK, T, TK> RetListAppendRetMulti<T, TK> for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn>
+        + NestedTupleSplice<<Tail::RetMultiOutput as RetList>::ListReturn>,
+    Tail: RetList + RetListAppendRetMulti<T, TK>,
+    T: RetListItem<Tail::Original, TK>,
+{
+    type RetMultiOutput =
+        ConsRetMulti<Head, <Tail as RetListAppendRetMulti<T, TK>>::RetMultiOutput, HK>;
+
+    fn append_ret_multi(self, t: T) -> Self::RetMultiOutput {
+        ConsRetMulti {
+            head: self.head,
+            tail: self.tail.append_ret_multi(t),
+            _kind: PhantomData,
+        }
+    }
+}
+
+pub struct ListWrapper<L: RetList>(pub(crate) L);
 
 pub fn new_list() -> ListWrapper<()> {
     ListWrapper(())
@@ -281,45 +770,173 @@ impl ListWrapper<()> {
     pub fn original_marker<O>(self) -> ListWrapper<OriginalMarker<O>> {
         ListWrapper(OriginalMarker::default())
     }
+
+    /// Like `original_marker`, for the results of a deploy.
+    pub fn deploy_marker<O>(self) -> ListWrapper<OriginalMarker<O, Deploy>> {
+        ListWrapper(OriginalMarker::default())
+    }
+
+    /// Like `original_marker`, for the results of an upgrade.
+    pub fn upgrade_marker<O>(self) -> ListWrapper<OriginalMarker<O, Upgrade>> {
+        ListWrapper(OriginalMarker::default())
+    }
 }
 
 impl<L: RetList> ListWrapper<L> {
-    pub fn eval(&self) -> <L::ListReturn as Flatten>::Unpacked
+    // The output is bound through `R` rather than `L::ListReturn` directly,
+    // so that an unflattenable output is reported with the `Flatten` diagnostic
+    // instead of as a missing `eval` method.
+    pub fn eval<R>(&self, raw: &RawResult) -> R::Unpacked
+    where
+        L: RetList<ListReturn = R>,
+        R: Flatten,
+    {
+        self.0.list_return(raw).flatten_unpack()
+    }
+
+    pub fn eval_reverse<R>(&self, raw: &RawResult) -> R::Unpacked
+    where
+        L: RetList<ListReturn = R>,
+        R: Flatten,
+    {
+        self.0.list_return_reverse(raw).flatten_unpack()
+    }
+
+    pub fn eval_ordered<R>(&self, raw: &RawResult, order: EvalOrder) -> R::Unpacked
+    where
+        L: RetList<ListReturn = R>,
+        R: Flatten,
+    {
+        match order {
+            EvalOrder::InOrder => self.eval(raw),
+            EvalOrder::Reverse => self.eval_reverse(raw),
+        }
+    }
+
+    /// Evaluates the same handlers against each of the results, lazily.
+    pub fn eval_many<'a, I, R>(&'a self, results: I) -> impl Iterator<Item = R::Unpacked> + 'a
+    where
+        I: IntoIterator<Item = RawResult>,
+        I::IntoIter: 'a,
+        L: RetList<ListReturn = R>,
+        R: Flatten,
+    {
+        results.into_iter().map(|raw| self.eval(&raw))
+    }
+
+    /// Evaluates the handlers against each of the results,
+    /// gathering the outputs of each returning handler in its own `Vec`.
+    pub fn collect_columns<I, R>(&self, results: I) -> <R::Columns as Flatten>::Unpacked
+    where
+        I: IntoIterator<Item = RawResult>,
+        L: RetList<ListReturn = R>,
+        R: NestedTupleColumns,
+        R::Columns: Flatten,
+    {
+        let mut columns = R::Columns::default();
+        for raw in results {
+            self.0.list_return(&raw).push_into(&mut columns);
+        }
+        columns.flatten_unpack()
+    }
+
+    /// Evaluates once and keeps the output, so it can be read again without re-running the handlers.
+    pub fn finish<R>(self, raw: &RawResult) -> EvaluatedList<R::Unpacked>
     where
-        L::ListReturn: Flatten,
+        L: RetList<ListReturn = R>,
+        R: Flatten,
     {
-        self.0.list_return().flatten_unpack()
+        EvaluatedList {
+            raw: raw.clone(),
+            output: self.eval(raw),
+        }
     }
 
-    pub fn returns<T>(self, rh: T) -> ListWrapper<L::RetOutput>
+    pub fn returns<T, TK>(self, rh: T) -> ListWrapper<L::RetOutput>
     where
-        T: RetListItem<L::Original>,
-        L: RetListAppendRet<T>,
+        T: RetListItem<L::Original, TK>,
+        L: RetListAppendRet<T, TK>,
     {
         ListWrapper(self.0.append_ret(rh))
     }
 
-    pub fn handle_result<T>(self, t: T) -> ListWrapper<L::NoRetOutput>
+    pub fn returns_multi<T, TK>(self, rh: T) -> ListWrapper<L::RetMultiOutput>
     where
-        T: RetListItem<L::Original, Returns = ()>,
-        L: RetListAppendNoRet<T>,
+        T: RetListItem<L::Original, TK>,
+        L: RetListAppendRetMulti<T, TK>,
+    {
+        ListWrapper(self.0.append_ret_multi(rh))
+    }
+
+    pub fn handle_result<T, TK>(self, t: T) -> ListWrapper<L::NoRetOutput>
+    where
+        T: RetListItem<L::Original, TK>,
+        T::Returns: UnitReturns,
+        L: RetListAppendNoRet<T, TK>,
     {
         ListWrapper(self.0.append_no_ret(t))
     }
 }
 
+/// The flattened output of a list, evaluated exactly once by `ListWrapper::finish`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluatedList<T> {
+    raw: RawResult,
+    output: T,
+}
+
+impl<T> EvaluatedList<T> {
+    pub fn output(&self) -> &T {
+        &self.output
+    }
+
+    pub fn into_output(self) -> T {
+        self.output
+    }
+
+    /// The result the handlers were evaluated against.
+    pub fn raw(&self) -> &RawResult {
+        &self.raw
+    }
+}
+
+/// Builds a `ListWrapper` in one expression, e.g.
+/// `ret_list![i32; returns ReturnsInt(100), handle PrintMessage::new("x"), returns ReturnsDefault]`.
+///
+/// `returns`, `returns_multi` and `handle` map to the `ListWrapper` methods
+/// `returns`, `returns_multi` and `handle_result`,
+/// so the resulting type is the same as that of the equivalent builder chain.
+#[macro_export]
+macro_rules! ret_list {
+    (@chain ($list:expr); ) => ( $list );
+    (@chain ($list:expr); returns $h:expr $(, $($rest:tt)*)?) => {
+        $crate::ret_list!(@chain ($list.returns($h)); $($($rest)*)?)
+    };
+    (@chain ($list:expr); returns_multi $h:expr $(, $($rest:tt)*)?) => {
+        $crate::ret_list!(@chain ($list.returns_multi($h)); $($($rest)*)?)
+    };
+    (@chain ($list:expr); handle $h:expr $(, $($rest:tt)*)?) => {
+        $crate::ret_list!(@chain ($list.handle_result($h)); $($($rest)*)?)
+    };
+    ($o:ty $(; $($rest:tt)*)?) => {
+        $crate::ret_list!(@chain ($crate::prototype_v6::new_list().original_marker::<$o>()); $($($rest)*)?)
+    };
+}
+
 pub fn example() {
+    let raw = RawResult::default();
+
     let list = new_list()
         .original_marker::<i32>()
         .returns(ReturnsInt(100))
         .returns(ReturnsInt(200))
-        .returns(ReturnsStr("hello!"))
+        .returns(ReturnsStr::new("hello!"))
         .handle_result(())
-        .handle_result(PrintMessage("handling results ..."))
+        .handle_result(PrintMessage::new("handling results ..."))
         .returns(ReturnsDefault)
         .returns(ReturnsInt(300));
 
-    let x = list.eval();
+    let x = list.eval(&raw);
 
     println!("{x:?}"); // (100, 200, "hello!", 0, 300)
 
@@ -327,7 +944,240 @@ pub fn example() {
         .original_marker::<Option<()>>()
         .returns(ReturnsDefault);
 
-    let single = list2.eval();
+    let single = list2.eval(&raw);
 
     println!("{single:?}"); // None
+
+    let mut slot = 0;
+    let cell = Rc::new(RefCell::new(0));
+    let list3 = new_list()
+        .original_marker::<u32>()
+        .handle_result(ReturnsInto::new(&mut slot))
+        .handle_result(ReturnsIntoCell(cell.clone()))
+        .returns(ReturnsDefault);
+
+    let single = list3.eval(&RawResult::default().with_out(42u32));
+    drop(list3);
+
+    println!("{single:?} {slot:?} {:?}", cell.borrow()); // 0 42 42
+
+    let raw = RawResult::default()
+        .with_out(5u32)
+        .with_out(true)
+        .with_out("abc");
+    let list4 = new_list()
+        .original_marker::<(u32, bool, String)>()
+        .returns(ReturnsDefault)
+        .returns_multi(ReturnsMultiValue::default());
+
+    let multi = list4.eval(&raw);
+
+    println!("{multi:?}"); // ((0, false, ""), 5, true, "abc")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec::TopEncode;
+
+    #[test]
+    fn test_returns_into() {
+        let mut slot = 5;
+        let cell = Rc::new(RefCell::new(7));
+        let list = new_list()
+            .original_marker::<i32>()
+            .returns(ReturnsInt(100))
+            .handle_result(ReturnsInto::new(&mut slot))
+            .handle_result(ReturnsIntoCell(cell.clone()))
+            .returns(ReturnsInt(200));
+
+        let raw = RawResult::default().with_out(-3i32);
+        assert_eq!(raw.out[0], (-3i32).top_encode());
+        assert_eq!(list.eval(&raw), (100, 200));
+        drop(list);
+        assert_eq!(slot, -3);
+        assert_eq!(*cell.borrow(), -3);
+    }
+
+    #[test]
+    #[should_panic(expected = "ReturnsIntoCell: too many results")]
+    fn test_returns_into_cell_multiple_results() {
+        let list = new_list()
+            .original_marker::<u32>()
+            .handle_result(ReturnsIntoCell(Rc::new(RefCell::new(0))));
+        list.eval(&RawResult::default().with_out(1u32).with_out(2u32));
+    }
+
+    #[test]
+    fn test_returns_multi() {
+        let raw = RawResult::default()
+            .with_out(5u32)
+            .with_out(true)
+            .with_out("abc");
+        let list = new_list()
+            .original_marker::<(u32, bool, String)>()
+            .returns_multi(ReturnsMultiValue::default())
+            .handle_result(PrintMessage::new("handling results ..."))
+            .returns(ReturnsDefault)
+            .returns_multi(ReturnsMultiValue::default());
+
+        assert_eq!(
+            list.eval(&raw),
+            (
+                5,
+                true,
+                "abc".to_string(),
+                (0, false, String::new()),
+                5,
+                true,
+                "abc".to_string()
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ReturnsMultiValue: too few results")]
+    fn test_returns_multi_too_few() {
+        let raw = RawResult::default().with_out(5u32);
+        let list = new_list()
+            .original_marker::<(u32, bool)>()
+            .returns_multi(ReturnsMultiValue::default());
+
+        list.eval(&raw);
+    }
+
+    #[test]
+    fn test_returns_iter() {
+        let mut raw = RawResult::default();
+        for i in 0..1000u32 {
+            raw = raw.with_out(i);
+        }
+        // not a valid u32, only fails if it ever gets decoded
+        raw = raw.with_out(vec![1u8; 5]);
+
+        let list = new_list()
+            .original_marker::<MultiValueEncoded<u32>>()
+            .returns(ReturnsIter::default());
+        let mut iter = list.eval(&raw);
+        assert_eq!(iter.len(), 1001);
+        assert_eq!(iter.by_ref().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(iter.nth(996), Some(999));
+
+        let list = new_list()
+            .original_marker::<MultiValueEncoded<u32>>()
+            .returns(ReturnsVec::default());
+        let raw = RawResult::default().with_out(3u32).with_out(4u32);
+        assert_eq!(list.eval(&raw), vec![3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ReturnsIter: input too long")]
+    fn test_returns_vec_invalid_item() {
+        let raw = RawResult::default().with_out(3u32).with_out(vec![1u8; 5]);
+        let list = new_list()
+            .original_marker::<MultiValueEncoded<u32>>()
+            .returns(ReturnsVec::default());
+
+        list.eval(&raw);
+    }
+
+    #[test]
+    fn test_eval_many() {
+        let list = new_list()
+            .original_marker::<(u32, String)>()
+            .returns_multi(ReturnsMultiValue::default())
+            .handle_result(PrintMessage::new("handling results ..."));
+        let results = || (0..3u32).map(|i| RawResult::default().with_out(i).with_out(i.to_string()));
+
+        let rows: Vec<_> = list.eval_many(results()).collect();
+        assert_eq!(
+            rows,
+            vec![
+                (0, "0".to_string()),
+                (1, "1".to_string()),
+                (2, "2".to_string())
+            ]
+        );
+
+        let (numbers, strings) = list.collect_columns(results());
+        assert_eq!(numbers, vec![0, 1, 2]);
+        assert_eq!(strings, vec!["0", "1", "2"]);
+
+        let single = new_list()
+            .original_marker::<(u32, String)>()
+            .returns(ReturnsDefault);
+        assert_eq!(single.collect_columns(results()).len(), 3);
+    }
+
+    struct RecordOrder(&'static str, Rc<RefCell<Vec<&'static str>>>);
+
+    impl<O> RetListItem<O> for RecordOrder {
+        type Returns = ();
+
+        fn single_return(&self, _raw: &RawResult) -> Self::Returns {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    #[test]
+    fn test_eval_order() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let list = new_list()
+            .original_marker::<(u8, u8)>()
+            .handle_result(RecordOrder("a", order.clone()))
+            .returns(ReturnsDefault)
+            .handle_result(RecordOrder("b", order.clone()))
+            .returns_multi(ReturnsMultiValue::default())
+            .handle_result(RecordOrder("c", order.clone()));
+        let raw = RawResult::default().with_out(1u8).with_out(2u8);
+
+        assert_eq!(list.eval(&raw), ((0, 0), 1, 2));
+        assert_eq!(order.take(), vec!["a", "b", "c"]);
+
+        assert_eq!(list.eval_reverse(&raw), ((0, 0), 1, 2));
+        assert_eq!(order.take(), vec!["c", "b", "a"]);
+
+        assert_eq!(list.eval_ordered(&raw, EvalOrder::InOrder), ((0, 0), 1, 2));
+        assert_eq!(order.take(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_finish() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        let list = new_list()
+            .original_marker::<(u32, String)>()
+            .handle_result(RecordOrder("a", order.clone()))
+            .returns_multi(ReturnsMultiValue::default())
+            .returns(ReturnsDefault);
+        let raw = RawResult::default().with_out(7u32).with_out("x");
+
+        let evaluated = list.finish(&raw);
+        assert_eq!(evaluated.raw(), &raw);
+        assert_eq!(evaluated.output().1, "x");
+        assert_eq!(evaluated.output(), &(7, "x".to_string(), (0, String::new())));
+        assert_eq!(order.take(), vec!["a"]);
+
+        let (n, _, _) = evaluated.into_output();
+        assert_eq!(n, 7);
+        assert!(order.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_ret_list_macro() {
+        type Expected =
+            ConsRet<ReturnsInt, ConsNoRet<PrintMessage, ConsRet<ReturnsDefault, OriginalMarker<i32>>>>;
+
+        let list: ListWrapper<Expected> = ret_list![i32; returns ReturnsInt(100), handle PrintMessage("x".into()), returns ReturnsDefault];
+        assert_eq!(list.eval(&RawResult::default()), (100, 0));
+
+        let list = ret_list![(u8, u8); returns_multi ReturnsMultiValue::default(), returns ReturnsDefault,];
+        let raw = RawResult::default().with_out(1u8).with_out(2u8);
+        assert_eq!(list.eval(&raw), (1, 2, (0, 0)));
+
+        let list = ret_list![Option<()>; returns ReturnsDefault,];
+        assert_eq!(list.eval(&RawResult::default()), None);
+
+        let list = ret_list![i32];
+        assert_eq!(list.eval(&RawResult::default()), ());
+    }
 }
@@ -0,0 +1,64 @@
+use std::rc::Rc;
+
+use crate::{
+    codec::{DecodeError, TopEncode},
+    deploy::Address,
+    events::Log,
+    payments::Payment,
+};
+
+/// What a transaction produced, before any result handler has looked at it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawResult {
+    /// One top-encoded buffer per returned value.
+    ///
+    /// Shared, so that handlers decoding lazily can keep it around without copying.
+    pub out: Rc<Vec<Vec<u8>>>,
+    /// Tokens the call sent back to the caller, in transfer order. Shared, like `out`.
+    pub transfers: Rc<Vec<Payment>>,
+    /// Events emitted during the call, in emission order. Shared, like `out`.
+    pub logs: Rc<Vec<Log>>,
+    /// Address of the new contract, for deploys.
+    pub new_address: Option<Address>,
+}
+
+impl RawResult {
+    pub fn new(out: Vec<Vec<u8>>) -> Self {
+        RawResult {
+            out: Rc::new(out),
+            transfers: Rc::default(),
+            logs: Rc::default(),
+            new_address: None,
+        }
+    }
+
+    /// Appends a result value, top-encoded. Mostly useful for building test inputs.
+    pub fn with_out<T: TopEncode>(mut self, value: T) -> Self {
+        Rc::make_mut(&mut self.out).push(value.top_encode());
+        self
+    }
+
+    pub fn with_transfer(mut self, payment: Payment) -> Self {
+        Rc::make_mut(&mut self.transfers).push(payment);
+        self
+    }
+
+    pub fn with_log(mut self, log: Log) -> Self {
+        Rc::make_mut(&mut self.logs).push(log);
+        self
+    }
+
+    pub fn with_new_address(mut self, address: Address) -> Self {
+        self.new_address = Some(address);
+        self
+    }
+
+    /// The only result buffer, for handlers that expect a single value.
+    pub fn single_out(&self) -> Result<&[u8], DecodeError> {
+        match self.out.as_slice() {
+            [bytes] => Ok(bytes),
+            [] => Err(DecodeError::MULTI_TOO_FEW_ARGS),
+            _ => Err(DecodeError::MULTI_TOO_MANY_ARGS),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{any::type_name, cell::RefCell, fmt::Debug, io::Write, rc::Rc};
+
+use crate::{
+    codec::{TopDecode, TopDecodeMulti},
+    json::JsonValue,
+    prototype_v6::{
+        ListWrapper, RetList, RetListAppendNoRet, RetListAppendRet, RetListItem, UnitReturns,
+    },
+    raw_result::RawResult,
+};
+
+/// What a logging handler saw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultRecord {
+    /// Position of the handler in its list.
+    pub handler_index: usize,
+    pub handler: &'static str,
+    pub original: &'static str,
+    /// The value, formatted with `Debug`.
+    pub value: String,
+}
+
+impl ResultRecord {
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("handler_index", self.handler_index.into()),
+            ("handler", self.handler.into()),
+            ("original", self.original.into()),
+            ("value", self.value.as_str().into()),
+        ])
+    }
+}
+
+pub trait ResultLogger {
+    fn log(&self, record: ResultRecord);
+}
+
+impl<G: ResultLogger + ?Sized> ResultLogger for &G {
+    fn log(&self, record: ResultRecord) {
+        (**self).log(record)
+    }
+}
+
+impl<G: ResultLogger + ?Sized> ResultLogger for Rc<G> {
+    fn log(&self, record: ResultRecord) {
+        (**self).log(record)
+    }
+}
+
+pub struct StdoutLogger;
+
+impl ResultLogger for StdoutLogger {
+    fn log(&self, record: ResultRecord) {
+        println!(
+            "[{}] {} ({}): {}",
+            record.handler_index, record.handler, record.original, record.value
+        );
+    }
+}
+
+/// Keeps the records, so that tests can inspect them.
+#[derive(Default)]
+pub struct MemoryLogger {
+    records: RefCell<Vec<ResultRecord>>,
+}
+
+impl MemoryLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> Vec<ResultRecord> {
+        self.records.borrow().clone()
+    }
+
+    pub fn take_records(&self) -> Vec<ResultRecord> {
+        self.records.take()
+    }
+}
+
+impl ResultLogger for MemoryLogger {
+    fn log(&self, record: ResultRecord) {
+        self.records.borrow_mut().push(record);
+    }
+}
+
+/// Writes one JSON object per record, one per line.
+pub struct JsonLinesLogger<W: Write> {
+    writer: RefCell<W>,
+}
+
+impl<W: Write> JsonLinesLogger<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesLogger {
+            writer: RefCell::new(writer),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+impl<W: Write> ResultLogger for JsonLinesLogger<W> {
+    fn log(&self, record: ResultRecord) {
+        writeln!(self.writer.borrow_mut(), "{}", record.to_json())
+            .expect("JsonLinesLogger: write failed");
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Logs the output of a returning handler, then passes it on unchanged.
+pub struct LogResult<H, G> {
+    handler_index: usize,
+    handler: H,
+    logger: G,
+}
+
+impl<Original, H, G> RetListItem<Original> for LogResult<H, G>
+where
+    H: RetListItem<Original>,
+    H::Returns: Debug,
+    G: ResultLogger,
+{
+    type Returns = H::Returns;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        let value = self.handler.single_return(raw);
+        self.logger.log(ResultRecord {
+            handler_index: self.handler_index,
+            handler: type_name::<H>(),
+            original: type_name::<Original>(),
+            value: format!("{value:?}"),
+        });
+        value
+    }
+}
+
+/// Decodes the result as `Original` and logs it, the structured counterpart of `PrintMessage`.
+pub struct LogDecoded<G> {
+    handler_index: usize,
+    logger: G,
+}
+
+impl<Original, G> RetListItem<Original> for LogDecoded<G>
+where
+    Original: TopDecode + Debug,
+    G: ResultLogger,
+{
+    type Returns = ();
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        let value = match <(Original,)>::multi_decode(&raw.out) {
+            Ok((decoded, ())) => format!("{decoded:?}"),
+            Err(err) => format!("<decode error: {err}>"),
+        };
+        self.logger.log(ResultRecord {
+            handler_index: self.handler_index,
+            handler: type_name::<Self>(),
+            original: type_name::<Original>(),
+            value,
+        });
+    }
+}
+
+impl<L: RetList> ListWrapper<L> {
+    /// Same as `returns`, but also logs the handler output.
+    pub fn returns_logged<T, G>(self, rh: T, logger: G) -> ListWrapper<L::RetOutput>
+    where
+        LogResult<T, G>: RetListItem<L::Original>,
+        L: RetListAppendRet<LogResult<T, G>>,
+    {
+        let handler_index = L::HANDLER_COUNT;
+        self.returns(LogResult {
+            handler_index,
+            handler: rh,
+            logger,
+        })
+    }
+
+    pub fn log_result<G>(self, logger: G) -> ListWrapper<L::NoRetOutput>
+    where
+        LogDecoded<G>: RetListItem<L::Original, Returns: UnitReturns>,
+        L: RetListAppendNoRet<LogDecoded<G>>,
+    {
+        let handler_index = L::HANDLER_COUNT;
+        self.handle_result(LogDecoded {
+            handler_index,
+            logger,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prototype_v6::*;
+
+    #[test]
+    fn test_memory_logger() {
+        let logger = MemoryLogger::new();
+        let list = new_list()
+            .original_marker::<i32>()
+            .returns(ReturnsDefault)
+            .log_result(&logger)
+            .returns_logged(ReturnsInt(100), &logger);
+
+        let raw = RawResult::default().with_out(-5i32);
+        assert_eq!(list.eval(&raw), (0, 100));
+
+        let records = logger.take_records();
+        assert_eq!(
+            records,
+            vec![
+                ResultRecord {
+                    handler_index: 1,
+                    handler: type_name::<LogDecoded<&MemoryLogger>>(),
+                    original: "i32",
+                    value: "-5".to_string(),
+                },
+                ResultRecord {
+                    handler_index: 2,
+                    handler: type_name::<ReturnsInt>(),
+                    original: "i32",
+                    value: "100".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_lines_logger() {
+        let logger = JsonLinesLogger::new(Vec::new());
+        let list = new_list()
+            .original_marker::<String>()
+            .log_result(&logger)
+            .returns_logged(ReturnsDefault, &logger);
+
+        list.eval(&RawResult::default().with_out("say \"hi\""));
+        list.eval(&RawResult::default().with_out(vec![0xffu8]));
+
+        let output = String::from_utf8(logger.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with(r#","original":"alloc::string::String","value":"\"say \\\"hi\\\"\""}"#));
+        assert!(lines[1].starts_with(r#"{"handler_index":1,"handler":"rh_prototype::prototype_v6::ReturnsDefault""#));
+        assert!(lines[2].ends_with(r#""value":"<decode error: utf-8 decode error>"}"#));
+    }
+}
@@ -0,0 +1,626 @@
+use std::{any::type_name, fmt, fmt::Debug, fs, io, path::Path, rc::Rc};
+
+use crate::{
+    codec::TopDecode,
+    deploy::Address,
+    dyn_ret_list::DynRetList,
+    encoding::{hex_decode, hex_encode},
+    events::Log,
+    expectations::{
+        out_from_json, out_to_json, transfers_from_json, transfers_to_json, Expectation,
+    },
+    json::{JsonError, JsonValue},
+    nested_tuples::{Flatten, NestedTupleSplice},
+    prototype_v6::{
+        ConsNoRet, ConsRet, ConsRetMulti, ListWrapper, OriginalMarker, RetList, RetListItem,
+        TxKind, UnitReturns,
+    },
+    raw_result::RawResult,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectationOutcome {
+    pub expectation: Expectation,
+    /// `None` if the check passed.
+    pub error: Option<String>,
+}
+
+impl ExpectationOutcome {
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// What one handler did during a traced evaluation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerTrace {
+    pub handler: String,
+    /// The value, formatted with `Debug`. `None` for handlers that return nothing.
+    pub output: Option<String>,
+    pub expectation: Option<ExpectationOutcome>,
+}
+
+impl HandlerTrace {
+    pub fn to_json(&self) -> JsonValue {
+        let mut fields = vec![("handler".to_string(), self.handler.as_str().into())];
+        if let Some(output) = &self.output {
+            fields.push(("output".to_string(), output.as_str().into()));
+        }
+        if let Some(outcome) = &self.expectation {
+            fields.push(("expectation".to_string(), outcome.expectation.to_json()));
+            fields.push(("passed".to_string(), outcome.passed().into()));
+            if let Some(error) = &outcome.error {
+                fields.push(("error".to_string(), error.as_str().into()));
+            }
+        }
+        JsonValue::Object(fields)
+    }
+
+    pub fn from_json(json: &JsonValue) -> Result<Self, ScenarioError> {
+        let handler = json
+            .get("handler")
+            .and_then(JsonValue::as_str)
+            .ok_or(ScenarioError::Format("handler name missing"))?;
+        let output = match json.get("output") {
+            Some(output) => Some(
+                output
+                    .as_str()
+                    .ok_or(ScenarioError::Format("handler output is not a string"))?
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let expectation = match json.get("expectation") {
+            Some(expectation) => Some(ExpectationOutcome {
+                expectation: Expectation::from_json(expectation)
+                    .ok_or(ScenarioError::Format("invalid expectation"))?,
+                error: json
+                    .get("error")
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string),
+            }),
+            None => None,
+        };
+        Ok(HandlerTrace {
+            handler: handler.to_string(),
+            output,
+            expectation,
+        })
+    }
+}
+
+/// One evaluation of a handler list: the input, and what each handler did, in push order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioStep {
+    pub raw: RawResult,
+    pub handlers: Vec<HandlerTrace>,
+}
+
+impl ScenarioStep {
+    /// All expectations passed.
+    pub fn passed(&self) -> bool {
+        self.handlers
+            .iter()
+            .filter_map(|trace| trace.expectation.as_ref())
+            .all(ExpectationOutcome::passed)
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        let mut raw = vec![("out".to_string(), out_to_json(&self.raw.out))];
+        if !self.raw.transfers.is_empty() {
+            raw.push((
+                "transfers".to_string(),
+                transfers_to_json(&self.raw.transfers),
+            ));
+        }
+        if !self.raw.logs.is_empty() {
+            raw.push((
+                "logs".to_string(),
+                JsonValue::Array(self.raw.logs.iter().map(Log::to_json).collect()),
+            ));
+        }
+        if let Some(address) = &self.raw.new_address {
+            raw.push((
+                "new_address".to_string(),
+                format!("0x{}", hex_encode(address.as_bytes())).into(),
+            ));
+        }
+        JsonValue::object([
+            ("raw", JsonValue::Object(raw)),
+            (
+                "handlers",
+                JsonValue::Array(self.handlers.iter().map(HandlerTrace::to_json).collect()),
+            ),
+        ])
+    }
+
+    pub fn from_json(json: &JsonValue) -> Result<Self, ScenarioError> {
+        let raw = json
+            .get("raw")
+            .ok_or(ScenarioError::Format("raw result missing"))?;
+        let out = raw
+            .get("out")
+            .and_then(out_from_json)
+            .ok_or(ScenarioError::Format("invalid raw result"))?;
+        let transfers = match raw.get("transfers") {
+            Some(transfers) => transfers_from_json(transfers)
+                .ok_or(ScenarioError::Format("invalid transfer list"))?,
+            None => Vec::new(),
+        };
+        let logs = match raw.get("logs").map(JsonValue::as_array) {
+            Some(Some(logs)) => logs
+                .iter()
+                .map(Log::from_json)
+                .collect::<Option<Vec<_>>>()
+                .ok_or(ScenarioError::Format("invalid log"))?,
+            Some(None) => return Err(ScenarioError::Format("invalid log list")),
+            None => Vec::new(),
+        };
+        let new_address = match raw.get("new_address") {
+            Some(address) => Some(
+                address
+                    .as_str()
+                    .and_then(|address| address.strip_prefix("0x"))
+                    .and_then(|address| hex_decode(address).ok())
+                    .and_then(|bytes| Address::top_decode(&bytes).ok())
+                    .ok_or(ScenarioError::Format("invalid new address"))?,
+            ),
+            None => None,
+        };
+        let handlers = json
+            .get("handlers")
+            .and_then(JsonValue::as_array)
+            .ok_or(ScenarioError::Format("handler list missing"))?
+            .iter()
+            .map(HandlerTrace::from_json)
+            .collect::<Result<_, _>>()?;
+        Ok(ScenarioStep {
+            raw: RawResult {
+                out: Rc::new(out),
+                transfers: Rc::new(transfers),
+                logs: Rc::new(logs),
+                new_address,
+            },
+            handlers,
+        })
+    }
+
+    /// The recorded expectations, as a list that no longer needs the original handler types.
+    pub fn replay_list(&self) -> DynRetList<()> {
+        self.handlers
+            .iter()
+            .filter_map(|trace| trace.expectation.as_ref())
+            .fold(DynRetList::new(), |list, outcome| {
+                list.returns(CheckExpectation(outcome.expectation.clone()))
+            })
+    }
+
+    /// Checks the recorded expectations again, against the recorded raw result.
+    ///
+    /// One outcome per expectation, in push order.
+    pub fn replay(&self) -> Vec<Result<(), String>> {
+        self.replay_list()
+            .eval(&self.raw)
+            .into_iter()
+            .map(|output| {
+                *output
+                    .downcast::<Result<(), String>>()
+                    .expect("replay list only holds CheckExpectation")
+            })
+            .collect()
+    }
+}
+
+/// Recorded evaluations, to be saved as a golden file and replayed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scenario {
+    pub steps: Vec<ScenarioStep>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates the list and records the step.
+    pub fn record<L, R>(&mut self, list: &ListWrapper<L>, raw: &RawResult) -> R::Unpacked
+    where
+        L: TraceList<ListReturn = R>,
+        R: Flatten,
+    {
+        let (output, step) = list.eval_traced(raw);
+        self.steps.push(step);
+        output
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([(
+            "steps",
+            JsonValue::Array(self.steps.iter().map(ScenarioStep::to_json).collect()),
+        )])
+    }
+
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, format!("{}\n", self.to_json()))
+    }
+
+    pub fn from_json(json: &JsonValue) -> Result<Self, ScenarioError> {
+        let steps = json
+            .get("steps")
+            .and_then(JsonValue::as_array)
+            .ok_or(ScenarioError::Format("step list missing"))?
+            .iter()
+            .map(ScenarioStep::from_json)
+            .collect::<Result<_, _>>()?;
+        Ok(Scenario { steps })
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, ScenarioError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| ScenarioError::Io(err.to_string()))?;
+        Scenario::from_json(&JsonValue::parse(&contents)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScenarioError {
+    Io(String),
+    Json(JsonError),
+    /// Valid JSON, but not a scenario.
+    Format(&'static str),
+}
+
+impl From<JsonError> for ScenarioError {
+    fn from(err: JsonError) -> Self {
+        ScenarioError::Json(err)
+    }
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(message) => write!(f, "cannot read scenario: {message}"),
+            ScenarioError::Json(err) => write!(f, "invalid JSON: {err}"),
+            ScenarioError::Format(message) => write!(f, "invalid scenario: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+/// Replays a recorded expectation, for any result type.
+///
+/// Returns the outcome instead of panicking, so that a runner can report every failure.
+pub struct CheckExpectation(pub Expectation);
+
+impl<Original> RetListItem<Original> for CheckExpectation {
+    type Returns = Result<(), String>;
+
+    fn single_return(&self, raw: &RawResult) -> Self::Returns {
+        self.0.check(raw)
+    }
+
+    fn expectation(&self) -> Option<Expectation> {
+        Some(self.0.clone())
+    }
+}
+
+/// Handler lists whose evaluation can be traced.
+///
+/// Returning handlers need `Debug` outputs.
+/// Expectations are checked before their handler runs, and recorded for every handler.
+/// A handler that returns nothing is skipped after a failed check, instead of panicking.
+pub trait TraceList: RetList {
+    fn list_return_traced(
+        &self,
+        raw: &RawResult,
+        trace: &mut Vec<HandlerTrace>,
+    ) -> Self::ListReturn;
+}
+
+impl TraceList for () {
+    fn list_return_traced(
+        &self,
+        _raw: &RawResult,
+        _trace: &mut Vec<HandlerTrace>,
+    ) -> Self::ListReturn {
+    }
+}
+
+impl<O, K: TxKind> TraceList for OriginalMarker<O, K> {
+    fn list_return_traced(
+        &self,
+        _raw: &RawResult,
+        _trace: &mut Vec<HandlerTrace>,
+    ) -> Self::ListReturn {
+    }
+}
+
+fn check_expectation<H, Original, HK>(handler: &H, raw: &RawResult) -> Option<ExpectationOutcome>
+where
+    H: RetListItem<Original, HK>,
+{
+    handler.expectation().map(|expectation| {
+        let error = expectation.check(raw).err();
+        ExpectationOutcome { expectation, error }
+    })
+}
+
+/// Returning handlers run even if their expectation failed, since their output is needed.
+fn trace_value<H, Original, HK>(
+    handler: &H,
+    raw: &RawResult,
+    trace: &mut Vec<HandlerTrace>,
+) -> H::Returns
+where
+    H: RetListItem<Original, HK>,
+    H::Returns: Debug,
+{
+    let expectation = check_expectation(handler, raw);
+    let value = handler.single_return(raw);
+    trace.push(HandlerTrace {
+        handler: type_name::<H>().to_string(),
+        output: Some(format!("{value:?}")),
+        expectation,
+    });
+    value
+}
+
+impl<Head, Tail, HK> TraceList for ConsRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: Debug,
+    Tail: TraceList,
+{
+    fn list_return_traced(
+        &self,
+        raw: &RawResult,
+        trace: &mut Vec<HandlerTrace>,
+    ) -> Self::ListReturn {
+        let head_ret = trace_value(&self.head, raw, trace);
+        let tail_ret = self.tail.list_return_traced(raw, trace);
+        (head_ret, tail_ret)
+    }
+}
+
+impl<Head, Tail, HK> TraceList for ConsNoRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
+    Tail: TraceList,
+{
+    fn list_return_traced(
+        &self,
+        raw: &RawResult,
+        trace: &mut Vec<HandlerTrace>,
+    ) -> Self::ListReturn {
+        let expectation = check_expectation(&self.head, raw);
+        if expectation.as_ref().is_none_or(ExpectationOutcome::passed) {
+            self.head.single_return(raw);
+        }
+        trace.push(HandlerTrace {
+            handler: type_name::<Head>().to_string(),
+            output: None,
+            expectation,
+        });
+        self.tail.list_return_traced(raw, trace)
+    }
+}
+
+impl<Head, Tail, HK> TraceList for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn> + Debug,
+    Tail: TraceList,
+{
+    fn list_return_traced(
+        &self,
+        raw: &RawResult,
+        trace: &mut Vec<HandlerTrace>,
+    ) -> Self::ListReturn {
+        let head_ret = trace_value(&self.head, raw, trace);
+        let tail_ret = self.tail.list_return_traced(raw, trace);
+        head_ret.splice(tail_ret)
+    }
+}
+
+impl<L: RetList> ListWrapper<L> {
+    /// Same as `eval`, but also records what each handler did.
+    pub fn eval_traced<R>(&self, raw: &RawResult) -> (R::Unpacked, ScenarioStep)
+    where
+        L: TraceList<ListReturn = R>,
+        R: Flatten,
+    {
+        let mut handlers = Vec::new();
+        let output = self.0.list_return_traced(raw, &mut handlers);
+        let step = ScenarioStep {
+            raw: raw.clone(),
+            handlers,
+        };
+        (output.flatten_unpack(), step)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        codec::MultiValueEncoded,
+        deploy::Address,
+        events::{Event, ExpectEvent},
+        expectations::{ExpectOut, ExpectValue},
+        payments::{ExpectTransfer, Payment},
+        prototype_v6::*,
+    };
+
+    #[test]
+    fn test_trace() {
+        let list = new_list()
+            .original_marker::<i32>()
+            .returns(ReturnsInt(100))
+            .handle_result(ExpectValue(-5i32))
+            .returns(ReturnsDefault);
+
+        let mut scenario = Scenario::new();
+        assert_eq!(
+            scenario.record(&list, &RawResult::default().with_out(-5i32)),
+            (100, 0)
+        );
+        assert_eq!(
+            scenario.record(&list, &RawResult::default().with_out(7i32)),
+            (100, 0)
+        );
+
+        assert!(scenario.steps[0].passed());
+        assert!(!scenario.steps[1].passed());
+        assert_eq!(
+            scenario.steps[1].handlers[1],
+            HandlerTrace {
+                handler: type_name::<ExpectValue<i32>>().to_string(),
+                output: None,
+                expectation: Some(ExpectationOutcome {
+                    expectation: Expectation::Out(vec![vec![0xfb]]),
+                    error: Some(r#"expected out ["0xfb"], got ["0x07"]"#.to_string()),
+                }),
+            }
+        );
+
+        assert_eq!(
+            scenario.steps[0].to_json().to_string(),
+            concat!(
+                r#"{"raw":{"out":["0xfb"]},"handlers":["#,
+                r#"{"handler":"rh_prototype::prototype_v6::ReturnsInt","output":"100"},"#,
+                r#"{"handler":"rh_prototype::expectations::ExpectValue<i32>","expectation":{"out":["0xfb"]},"passed":true},"#,
+                r#"{"handler":"rh_prototype::prototype_v6::ReturnsDefault","output":"0"}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_scenario() {
+        let list = new_list()
+            .original_marker::<MultiValueEncoded<u8>>()
+            .handle_result(ExpectOut(vec![vec![1], vec![]]))
+            .returns(ReturnsVec::default());
+
+        let mut scenario = Scenario::new();
+        let raw = RawResult::default().with_out(1u8).with_out(0u8);
+        assert_eq!(scenario.record(&list, &raw), vec![1, 0]);
+
+        // Unique per process, so that concurrent test runs do not share the file.
+        let path = std::env::temp_dir().join(format!(
+            "rh_prototype_test_write_scenario_{}.json",
+            std::process::id()
+        ));
+        scenario.write_to_file(&path).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written, format!("{}\n", scenario.to_json()));
+        assert!(written.starts_with(r#"{"steps":[{"raw":{"out":["0x01","0x"]}"#));
+    }
+
+    #[test]
+    fn test_replay() {
+        let list = new_list()
+            .original_marker::<u32>()
+            .handle_result(ExpectValue(5u32))
+            .returns(ReturnsDefault)
+            .handle_result(ExpectOut(vec![vec![5]]));
+
+        let mut scenario = Scenario::new();
+        scenario.record(&list, &RawResult::default().with_out(5u32));
+        scenario.record(&list, &RawResult::default().with_out(6u32));
+
+        let parsed =
+            Scenario::from_json(&JsonValue::parse(&scenario.to_json().to_string()).unwrap());
+        assert_eq!(parsed, Ok(scenario.clone()));
+
+        assert_eq!(scenario.steps[0].replay(), vec![Ok(()), Ok(())]);
+        let error = r#"expected out ["0x05"], got ["0x06"]"#.to_string();
+        assert_eq!(
+            scenario.steps[1].replay(),
+            vec![Err(error.clone()), Err(error)]
+        );
+
+        assert_eq!(
+            Scenario::from_json(
+                &JsonValue::parse(r#"{"steps":[{"raw":{"out":["05"]},"handlers":[]}]}"#).unwrap()
+            ),
+            Err(ScenarioError::Format("invalid raw result"))
+        );
+    }
+
+    #[test]
+    fn test_trace_returning_expectation() {
+        let list = new_list()
+            .original_marker::<u32>()
+            .returns(CheckExpectation(Expectation::Out(vec![vec![5]])))
+            .returns(ReturnsDefault);
+
+        let mut scenario = Scenario::new();
+        let output = scenario.record(&list, &RawResult::default().with_out(6u32));
+        let error = r#"expected out ["0x05"], got ["0x06"]"#.to_string();
+        assert_eq!(output, (Err(error.clone()), 0));
+
+        let step = &scenario.steps[0];
+        assert_eq!(
+            step.handlers[0].expectation,
+            Some(ExpectationOutcome {
+                expectation: Expectation::Out(vec![vec![5]]),
+                error: Some(error.clone()),
+            })
+        );
+        assert!(!step.passed());
+        assert_eq!(step.replay(), vec![Err(error)]);
+    }
+
+    struct Deposit(u8, String);
+
+    impl Event for Deposit {
+        const IDENTIFIER: &'static str = "deposit";
+
+        type Topics = (u8,);
+        type Data = (String,);
+
+        fn from_fields(id: u8, memo: String) -> Self {
+            Deposit(id, memo)
+        }
+    }
+
+    #[test]
+    fn test_replay_with_transfers_logs_and_address() {
+        let list = new_list()
+            .original_marker::<()>()
+            .handle_result(ExpectTransfer(Payment::egld(10u8)))
+            .handle_result(ExpectEvent::<Deposit>::default());
+        let mut scenario = Scenario::new();
+        scenario.record(
+            &list,
+            &RawResult::default().with_transfer(Payment::egld(10u8)),
+        );
+        scenario.record(
+            &list,
+            &RawResult::default()
+                .with_new_address(Address([0xab; 32]))
+                .with_transfer(Payment::esdt("TKN-123456", 1, 10u8))
+                .with_log(Log::new("deposit").with_topic(7u8).with_data("memo")),
+        );
+
+        let parsed =
+            Scenario::from_json(&JsonValue::parse(&scenario.to_json().to_string()).unwrap());
+        assert_eq!(parsed, Ok(scenario.clone()));
+        assert_eq!(
+            scenario.steps[0].replay(),
+            vec![Ok(()), Err("no `deposit` event".to_string())]
+        );
+        assert_eq!(
+            scenario.steps[1].replay(),
+            vec![
+                Err(
+                    r#"expected transfer 10 EGLD, got [{"token":"TKN-123456","nonce":"1","amount":"10"}]"#
+                        .to_string()
+                ),
+                Ok(())
+            ]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use crate::{
+    abi::output_name,
+    nested_tuples::{NestedTuple, NestedTupleSplice},
+    prototype_v6::{
+        ConsNoRet, ConsRet, ConsRetMulti, ListWrapper, OriginalMarker, RetList, RetListItem,
+        TxKind, UnitReturns,
+    },
+    raw_result::RawResult,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One element per output, in order.
+    #[default]
+    Array,
+    /// Keyed by handler label, or `output_<index>` for unlabelled handlers.
+    Object,
+}
+
+/// Nested tuples whose elements are all `Serialize`.
+pub trait SerializeElements: NestedTuple {
+    fn serialize_elements<S: SerializeSeq>(&self, seq: &mut S) -> Result<(), S::Error>;
+
+    fn serialize_entries<M: SerializeMap>(
+        &self,
+        names: &[String],
+        map: &mut M,
+    ) -> Result<(), M::Error>;
+}
+
+impl SerializeElements for () {
+    fn serialize_elements<S: SerializeSeq>(&self, _seq: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+
+    fn serialize_entries<M: SerializeMap>(
+        &self,
+        _names: &[String],
+        _map: &mut M,
+    ) -> Result<(), M::Error> {
+        Ok(())
+    }
+}
+
+impl<Head, Tail> SerializeElements for (Head, Tail)
+where
+    Head: Serialize,
+    Tail: SerializeElements,
+{
+    fn serialize_elements<S: SerializeSeq>(&self, seq: &mut S) -> Result<(), S::Error> {
+        seq.serialize_element(&self.0)?;
+        self.1.serialize_elements(seq)
+    }
+
+    fn serialize_entries<M: SerializeMap>(
+        &self,
+        names: &[String],
+        map: &mut M,
+    ) -> Result<(), M::Error> {
+        map.serialize_entry(&names[0], &self.0)?;
+        self.1.serialize_entries(&names[1..], map)
+    }
+}
+
+/// Names of the output elements, in output order.
+pub trait OutputNames: RetList {
+    fn output_names(&self, names: &mut Vec<String>);
+}
+
+impl OutputNames for () {
+    fn output_names(&self, _names: &mut Vec<String>) {}
+}
+
+impl<O, K: TxKind> OutputNames for OriginalMarker<O, K> {
+    fn output_names(&self, _names: &mut Vec<String>) {}
+}
+
+impl<Head, Tail, HK> OutputNames for ConsRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Tail: OutputNames,
+{
+    fn output_names(&self, names: &mut Vec<String>) {
+        names.push(output_name(self.head.label(), 0, 1, names.len()));
+        self.tail.output_names(names);
+    }
+}
+
+impl<Head, Tail, HK> OutputNames for ConsNoRet<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK, Returns: UnitReturns>,
+    Tail: OutputNames,
+{
+    fn output_names(&self, names: &mut Vec<String>) {
+        self.tail.output_names(names);
+    }
+}
+
+impl<Head, Tail, HK> OutputNames for ConsRetMulti<Head, Tail, HK>
+where
+    Head: RetListItem<Tail::Original, HK>,
+    Head::Returns: NestedTupleSplice<Tail::ListReturn> + NestedTuple,
+    Tail: OutputNames,
+{
+    fn output_names(&self, names: &mut Vec<String>) {
+        let parts = Head::Returns::LEN;
+        for part in 0..parts {
+            names.push(output_name(self.head.label(), part, parts, names.len()));
+        }
+        self.tail.output_names(names);
+    }
+}
+
+/// The output of a list, serializable as an array or as an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializableOutput<R> {
+    pub names: Vec<String>,
+    pub values: R,
+    pub format: OutputFormat,
+}
+
+impl<R: SerializeElements> Serialize for SerializableOutput<R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.format {
+            OutputFormat::Array => {
+                let mut seq = serializer.serialize_seq(Some(R::LEN))?;
+                self.values.serialize_elements(&mut seq)?;
+                seq.end()
+            }
+            OutputFormat::Object => {
+                let mut map = serializer.serialize_map(Some(R::LEN))?;
+                self.values.serialize_entries(&self.names, &mut map)?;
+                map.end()
+            }
+        }
+    }
+}
+
+impl<L: RetList> ListWrapper<L> {
+    /// Same as `eval`, but keeps the output in a form that can be serialized.
+    ///
+    /// Unlike the flattened output, a single value is still serialized as an array of one.
+    pub fn eval_serializable(
+        &self,
+        raw: &RawResult,
+        format: OutputFormat,
+    ) -> SerializableOutput<L::ListReturn>
+    where
+        L: OutputNames,
+        L::ListReturn: SerializeElements,
+    {
+        let mut names = Vec::with_capacity(L::ListReturn::LEN);
+        self.0.output_names(&mut names);
+        SerializableOutput {
+            names,
+            values: self.0.list_return(raw),
+            format,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{codec::MultiValueEncoded, expectations::ExpectOut, prototype_v6::*};
+
+    #[test]
+    fn test_serialize_output() {
+        let list = new_list()
+            .original_marker::<(u32, String)>()
+            .returns_multi(Labelled::new("pair", ReturnsMultiValue::default()))
+            .handle_result(PrintMessage::new("..."))
+            .returns(ReturnsDefault);
+        let raw = RawResult::default().with_out(5u32).with_out("five");
+
+        let array = list.eval_serializable(&raw, OutputFormat::Array);
+        assert_eq!(
+            serde_json::to_string(&array).unwrap(),
+            r#"[5,"five",[0,""]]"#
+        );
+        let object = list.eval_serializable(&raw, OutputFormat::Object);
+        assert_eq!(
+            serde_json::to_string(&object).unwrap(),
+            r#"{"pair_0":5,"pair_1":"five","output_2":[0,""]}"#
+        );
+
+        let single = new_list()
+            .original_marker::<MultiValueEncoded<u8>>()
+            .returns(Labelled::new("items", ReturnsVec::default()));
+        let raw = RawResult::default().with_out(1u8).with_out(2u8);
+        assert_eq!(
+            serde_json::to_string(&single.eval_serializable(&raw, OutputFormat::Array)).unwrap(),
+            "[[1,2]]"
+        );
+        assert_eq!(
+            serde_json::to_string(&single.eval_serializable(&raw, OutputFormat::Object)).unwrap(),
+            r#"{"items":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn test_handler_config() {
+        // Owned, as if read from a file, so that nothing can borrow from it.
+        let config = String::from(
+            r#"[100, null, {"label": "out", "handler": [[1], []]}, "done", "hello!"]"#,
+        );
+        let handlers: (
+            ReturnsInt,
+            ReturnsDefault,
+            Labelled<ExpectOut>,
+            PrintMessage,
+            ReturnsStr,
+        ) = serde_json::from_str(&config).unwrap();
+        let serialized = serde_json::to_string(&handlers).unwrap();
+        drop(config);
+
+        let (int, _, expect, print, returns_str) = &handlers;
+        assert_eq!(int.0, 100);
+        assert_eq!(expect.label, "out");
+        assert_eq!(expect.handler.0, vec![vec![1], vec![]]);
+        assert_eq!(print.0, "done");
+        assert_eq!(returns_str.0, "hello!");
+        assert_eq!(
+            serialized,
+            r#"[100,null,{"label":"out","handler":[[1],[]]},"done","hello!"]"#
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{
+    mock_vm::{MockVm, TxCall, TxError},
+    nested_tuples::{Flatten, NestedTuple, NestedTupleAppend},
+    prototype_v6::{ListWrapper, RetList},
+};
+
+/// A call, together with the handlers for its result.
+pub struct Tx<L: RetList> {
+    pub call: TxCall,
+    pub results: ListWrapper<L>,
+}
+
+impl TxCall {
+    pub fn with_results<L: RetList>(self, results: ListWrapper<L>) -> Tx<L> {
+        Tx {
+            call: self,
+            results,
+        }
+    }
+}
+
+impl<L: RetList> Tx<L> {
+    /// Handler panics are not caught here. Batches report them as `TxError::HandlerPanicked`.
+    pub fn run<R>(&self, vm: &mut MockVm) -> Result<R::Unpacked, TxError>
+    where
+        L: RetList<ListReturn = R>,
+        R: Flatten,
+    {
+        vm.execute(&self.call).map(|raw| self.results.eval(&raw))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecutionOrder {
+    /// In the order the transactions were pushed.
+    #[default]
+    InOrder,
+    Reverse,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Transactions after a failed one are not executed, and are reported as `TxError::Skipped`.
+    #[default]
+    StopOnFirstFailure,
+    Continue,
+}
+
+/// Nested tuple of transactions, `(Tx<L1>, (Tx<L2>, ()))`.
+pub trait TxBatchList: NestedTuple {
+    /// One `Result` per transaction, in the same positions as the transactions.
+    type Results: NestedTuple;
+
+    fn run_batch(&self, vm: &mut MockVm, batch: &BatchSettings, failed: &mut bool)
+        -> Self::Results;
+}
+
+impl TxBatchList for () {
+    type Results = ();
+
+    fn run_batch(
+        &self,
+        _vm: &mut MockVm,
+        _batch: &BatchSettings,
+        _failed: &mut bool,
+    ) -> Self::Results {
+    }
+}
+
+impl<L, Tail> TxBatchList for (Tx<L>, Tail)
+where
+    L: RetList,
+    L::ListReturn: Flatten,
+    Tail: TxBatchList,
+{
+    type Results = (
+        Result<<L::ListReturn as Flatten>::Unpacked, TxError>,
+        Tail::Results,
+    );
+
+    fn run_batch(
+        &self,
+        vm: &mut MockVm,
+        batch: &BatchSettings,
+        failed: &mut bool,
+    ) -> Self::Results {
+        match batch.order {
+            ExecutionOrder::InOrder => {
+                let head = batch.run_one(&self.0, vm, failed);
+                let tail = self.1.run_batch(vm, batch, failed);
+                (head, tail)
+            }
+            ExecutionOrder::Reverse => {
+                let tail = self.1.run_batch(vm, batch, failed);
+                let head = batch.run_one(&self.0, vm, failed);
+                (head, tail)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSettings {
+    pub order: ExecutionOrder,
+    pub on_failure: FailurePolicy,
+}
+
+impl BatchSettings {
+    fn run_one<L, R>(
+        &self,
+        tx: &Tx<L>,
+        vm: &mut MockVm,
+        failed: &mut bool,
+    ) -> Result<R::Unpacked, TxError>
+    where
+        L: RetList<ListReturn = R>,
+        R: Flatten,
+    {
+        if *failed && self.on_failure == FailurePolicy::StopOnFirstFailure {
+            return Err(TxError::Skipped);
+        }
+        let result = vm.execute(&tx.call).and_then(|raw| {
+            panic::catch_unwind(AssertUnwindSafe(|| tx.results.eval(&raw)))
+                .map_err(|payload| TxError::HandlerPanicked(panic_message(payload.as_ref())))
+        });
+        *failed |= result.is_err();
+        result
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    match payload.downcast_ref::<String>() {
+        Some(message) => message.clone(),
+        None => payload.downcast_ref::<&str>().map_or_else(
+            || "unknown panic".to_string(),
+            |message| message.to_string(),
+        ),
+    }
+}
+
+/// Several transactions, each with its own handler list, run together.
+///
+/// A handler that panics fails only its own transaction, with `TxError::HandlerPanicked`.
+pub struct TxBatch<Txs: TxBatchList> {
+    txs: Txs,
+    settings: BatchSettings,
+}
+
+pub fn new_batch() -> TxBatch<()> {
+    TxBatch {
+        txs: (),
+        settings: BatchSettings::default(),
+    }
+}
+
+impl<Txs: TxBatchList> TxBatch<Txs> {
+    pub fn push<L>(self, tx: Tx<L>) -> TxBatch<Txs::Output>
+    where
+        L: RetList,
+        Txs: NestedTupleAppend<Tx<L>>,
+        <Txs as NestedTupleAppend<Tx<L>>>::Output: TxBatchList,
+    {
+        TxBatch {
+            txs: self.txs.append(tx),
+            settings: self.settings,
+        }
+    }
+
+    pub fn order(mut self, order: ExecutionOrder) -> Self {
+        self.settings.order = order;
+        self
+    }
+
+    pub fn on_failure(mut self, on_failure: FailurePolicy) -> Self {
+        self.settings.on_failure = on_failure;
+        self
+    }
+
+    pub fn run(&self, vm: &mut MockVm) -> Txs::Results {
+        let mut failed = false;
+        self.txs.run_batch(vm, &self.settings, &mut failed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        codec::{MultiValueEncoded, TopDecode},
+        prototype_v6::*,
+        raw_result::RawResult,
+    };
+
+    fn mock_vm() -> MockVm {
+        let mut vm = MockVm::new();
+        vm.register("double", |args| {
+            let n = u32::top_decode(&args[0]).unwrap();
+            Ok(RawResult::default().with_out(n * 2))
+        });
+        vm.register("range", |args| {
+            let n = u32::top_decode(&args[0]).unwrap();
+            Ok((0..n).fold(RawResult::default(), |raw, i| raw.with_out(i)))
+        });
+        vm.register("fail", |_| Err(TxError::user_error("nope")));
+        vm.register("garbage", |_| {
+            Ok(RawResult::default().with_out(vec![1u8; 5]))
+        });
+        vm
+    }
+
+    #[test]
+    fn test_batch() {
+        let batch = new_batch()
+            .push(
+                TxCall::new("double").with_arg(21u32).with_results(
+                    new_list()
+                        .original_marker::<(u32,)>()
+                        .returns_multi(ReturnsMultiValue::default()),
+                ),
+            )
+            .push(TxCall::new("fail").with_results(new_list().original_marker::<()>()))
+            .push(
+                TxCall::new("range").with_arg(3u32).with_results(
+                    new_list()
+                        .original_marker::<MultiValueEncoded<u32>>()
+                        .returns(ReturnsVec::default()),
+                ),
+            );
+
+        let mut vm = mock_vm();
+        let (doubled, (failed, (range, ()))) = batch.run(&mut vm);
+        assert_eq!(doubled, Ok(42));
+        assert_eq!(failed, Err(TxError::user_error("nope")));
+        assert_eq!(range, Err(TxError::Skipped));
+        assert_eq!(vm.executed(), ["double", "fail"]);
+
+        let batch = batch.on_failure(FailurePolicy::Continue);
+        let mut vm = mock_vm();
+        let (doubled, (failed, (range, ()))) = batch.run(&mut vm);
+        assert_eq!(doubled, Ok(42));
+        assert_eq!(failed, Err(TxError::user_error("nope")));
+        assert_eq!(range, Ok(vec![0, 1, 2]));
+        assert_eq!(vm.executed(), ["double", "fail", "range"]);
+
+        let batch = batch
+            .order(ExecutionOrder::Reverse)
+            .on_failure(FailurePolicy::StopOnFirstFailure);
+        let mut vm = mock_vm();
+        let (doubled, (failed, (range, ()))) = batch.run(&mut vm);
+        assert_eq!(doubled, Err(TxError::Skipped));
+        assert_eq!(failed, Err(TxError::user_error("nope")));
+        assert_eq!(range, Ok(vec![0, 1, 2]));
+        assert_eq!(vm.executed(), ["range", "fail"]);
+    }
+
+    #[test]
+    fn test_handler_panic() {
+        let batch = new_batch()
+            .push(
+                TxCall::new("garbage").with_results(
+                    new_list()
+                        .original_marker::<(u32,)>()
+                        .returns_multi(ReturnsMultiValue::default()),
+                ),
+            )
+            .push(
+                TxCall::new("double")
+                    .with_arg(1u32)
+                    .with_results(new_list().original_marker::<u32>().returns(ReturnsDefault)),
+            )
+            .on_failure(FailurePolicy::Continue);
+
+        let mut vm = mock_vm();
+        let (garbage, (doubled, ())) = batch.run(&mut vm);
+        assert_eq!(
+            garbage,
+            Err(TxError::HandlerPanicked(
+                "ReturnsMultiValue: input too long".to_string()
+            ))
+        );
+        assert_eq!(doubled, Ok(0));
+        assert_eq!(vm.executed(), ["garbage", "double"]);
+    }
+}
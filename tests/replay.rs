@@ -0,0 +1,46 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use rh_prototype::{
+    expectations::ExpectValue, prototype_v6::*, raw_result::RawResult, scenario::Scenario,
+};
+
+fn write_scenario(name: &str, out: u32) -> PathBuf {
+    let list = new_list()
+        .original_marker::<u32>()
+        .handle_result(ExpectValue(5u32))
+        .returns(ReturnsDefault);
+    let mut scenario = Scenario::new();
+    scenario.record(&list, &RawResult::default().with_out(out));
+
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    scenario.write_to_file(&path).unwrap();
+    path
+}
+
+fn replay(args: &[&Path]) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_replay"))
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+#[test]
+fn replay_exit_status() {
+    let passing = write_scenario("replay_passing.json", 5);
+    let failing = write_scenario("replay_failing.json", 6);
+    let missing = Path::new(env!("CARGO_TARGET_TMPDIR")).join("replay_missing.json");
+    let nested = Path::new(env!("CARGO_TARGET_TMPDIR")).join("replay_nested.json");
+    std::fs::write(&nested, "[".repeat(1_000_000)).unwrap();
+
+    assert_eq!(replay(&[&passing]), Some(0));
+    assert_eq!(replay(&[&failing]), Some(1));
+    assert_eq!(replay(&[&passing, &failing]), Some(1));
+    assert_eq!(replay(&[&missing]), Some(2));
+    assert_eq!(replay(&[&nested]), Some(2));
+    assert_eq!(replay(&[]), Some(2));
+}
@@ -0,0 +1,7 @@
+use rh_prototype::{presentation::ReturnsBech32Address, prototype_v6::*};
+
+fn main() {
+    let _ = new_list()
+        .original_marker::<Vec<u8>>()
+        .returns(ReturnsBech32Address::new("erd"));
+}
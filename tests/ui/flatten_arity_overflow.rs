@@ -0,0 +1,25 @@
+use rh_prototype::{prototype_v6::*, raw_result::RawResult};
+
+fn main() {
+    let list = new_list()
+        .original_marker::<i32>()
+        .returns(ReturnsInt(1))
+        .returns(ReturnsInt(2))
+        .returns(ReturnsInt(3))
+        .returns(ReturnsInt(4))
+        .returns(ReturnsInt(5))
+        .returns(ReturnsInt(6))
+        .returns(ReturnsInt(7))
+        .returns(ReturnsInt(8))
+        .returns(ReturnsInt(9))
+        .returns(ReturnsInt(10))
+        .returns(ReturnsInt(11))
+        .returns(ReturnsInt(12))
+        .returns(ReturnsInt(13))
+        .returns(ReturnsInt(14))
+        .returns(ReturnsInt(15))
+        .returns(ReturnsInt(16))
+        .returns(ReturnsInt(17));
+
+    let _ = list.eval(&RawResult::default());
+}
@@ -0,0 +1,8 @@
+use rh_prototype::prototype_v6::*;
+
+fn main() {
+    let mut slot = 0i32;
+    let _ = new_list()
+        .original_marker::<String>()
+        .handle_result(ReturnsInto::new(&mut slot));
+}
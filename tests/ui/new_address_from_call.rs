@@ -0,0 +1,10 @@
+use rh_prototype::{deploy::ReturnsNewAddress, prototype_v6::*};
+
+fn main() {
+    let _ = new_list()
+        .original_marker::<()>()
+        .returns(ReturnsNewAddress);
+    let _ = new_list()
+        .upgrade_marker::<()>()
+        .returns(ReturnsNewAddress);
+}
@@ -0,0 +1,7 @@
+use rh_prototype::prototype_v6::*;
+
+fn main() {
+    let _ = new_list()
+        .handle_result(PrintMessage::new("handling results ..."))
+        .original_marker::<i32>();
+}
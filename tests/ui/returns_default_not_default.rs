@@ -0,0 +1,9 @@
+use rh_prototype::prototype_v6::*;
+
+struct NoDefault;
+
+fn main() {
+    let _ = new_list()
+        .original_marker::<NoDefault>()
+        .returns(ReturnsDefault);
+}
@@ -0,0 +1,5 @@
+use rh_prototype::prototype_v6::*;
+
+fn main() {
+    let _ = new_list().returns(ReturnsInt(100));
+}
@@ -0,0 +1,7 @@
+use rh_prototype::prototype_v6::UnitReturns;
+
+struct Dropped;
+
+impl UnitReturns for Dropped {}
+
+fn main() {}